@@ -1,22 +1,61 @@
-use datatypes::partial_transformer::PartialTransformer;
+use clap::Parser;
 use datatypes::reader::EventIterator;
 use datatypes::world_builder::WorldBuilder;
+use std::collections::HashMap;
 use std::io::Write;
 
-use std::io::{self, BufRead};
+use std::io;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Check reconstructed books against recorded book tickers and report
+    /// divergence on stderr
+    #[arg(long)]
+    validate: bool,
+}
 
 fn main() -> io::Result<()> {
+    let args = Args::parse();
+
     let stdin = io::stdin();
     let reader = Box::new(stdin.lock());
     let event_iter = Box::new(EventIterator::new(reader));
-    
-    let world_builder = WorldBuilder::new(event_iter);
+
+    let world_builder = if args.validate {
+        WorldBuilder::validating(event_iter)
+    } else {
+        WorldBuilder::new(event_iter)
+    };
+
+    // divergences already reported, per symbol
+    let mut reported: HashMap<String, usize> = HashMap::new();
 
     for ob in world_builder {
+        let ob = ob.unwrap();
+
+        for (symbol, health) in &ob.book_health {
+            let n_reported = reported.entry(symbol.clone()).or_default();
+            if health.n_diverged > *n_reported {
+                let check = &health.last_check;
+                eprintln!(
+                    "{} diverged at update {} (ticker {}): book {:?}/{:?} ticker {:?}/{:?}",
+                    symbol,
+                    check.update_id,
+                    check.ticker_update_id,
+                    check.book_bid,
+                    check.book_ask,
+                    check.ticker_bid,
+                    check.ticker_ask
+                );
+                *n_reported = health.n_diverged;
+            }
+        }
+
         // instead of println, do this to prevent broken pipe errors
         // the error still happens, we just ignore it
         let mut stdout = io::stdout();
-        let _ = write!(stdout, "{}\n", serde_json::to_string(&ob.unwrap()).unwrap());
+        let _ = writeln!(stdout, "{}", serde_json::to_string(&ob).unwrap());
     }
 
     Ok(())
@@ -0,0 +1,188 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum BarKind {
+    /// A bar per fixed span of time
+    Time,
+    /// A bar per fixed number of samples
+    Tick,
+    /// A bar per fixed base asset volume
+    Volume,
+    /// A bar per fixed quote asset volume
+    Dollar,
+}
+
+/// One OHLCV bar.
+#[derive(Serialize, Debug, Clone)]
+pub struct Bar {
+    pub symbol: String,
+    // time bars span their whole bucket like the exchange's klines, other bars
+    // span their first to last sample, in milliseconds
+    pub open_time: u64,
+    pub close_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub n_ticks: usize,
+}
+
+impl Bar {
+    fn new(symbol: &str, open_time: u64, price: f64) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            open_time,
+            close_time: open_time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: 0.0,
+            quote_volume: 0.0,
+            n_ticks: 0,
+        }
+    }
+
+    fn add(&mut self, time: u64, price: f64, qty: f64) {
+        self.close_time = self.close_time.max(time);
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += qty;
+        self.quote_volume += price * qty;
+        self.n_ticks += 1;
+    }
+}
+
+/// Builds one symbol's samples into bars of a fixed size.
+///
+/// `size` is in milliseconds for time bars, samples for tick bars, base asset
+/// for volume bars and quote asset for dollar bars. A sample never gets split
+/// across bars, so volume and dollar bars overshoot by up to one sample.
+pub struct BarBuilder {
+    kind: BarKind,
+    size: f64,
+    current: Option<Bar>,
+}
+
+impl BarBuilder {
+    pub fn new(kind: BarKind, size: f64) -> Self {
+        Self {
+            kind,
+            size,
+            current: None,
+        }
+    }
+
+    fn bucket(&self, time: u64) -> u64 {
+        let size = self.size as u64;
+        time - time % size
+    }
+
+    /// Add a sample, returning the bar it completed if there is one.
+    pub fn push(&mut self, symbol: &str, time: u64, price: f64, qty: f64) -> Option<Bar> {
+        let mut closed = None;
+
+        // a time bar is only known to be complete once a later sample turns up
+        if self.kind == BarKind::Time {
+            let bucket = self.bucket(time);
+            if self.current.as_ref().is_some_and(|bar| bar.open_time != bucket) {
+                closed = self.current.take();
+            }
+            if self.current.is_none() {
+                let mut bar = Bar::new(symbol, bucket, price);
+                bar.close_time = bucket + self.size as u64 - 1;
+                self.current = Some(bar);
+            }
+        }
+
+        let bar = self.current.get_or_insert_with(|| Bar::new(symbol, time, price));
+        bar.add(time, price, qty);
+
+        let full = match self.kind {
+            BarKind::Time => false,
+            BarKind::Tick => bar.n_ticks as f64 >= self.size,
+            BarKind::Volume => bar.volume >= self.size,
+            BarKind::Dollar => bar.quote_volume >= self.size,
+        };
+
+        if full {
+            closed = self.current.take();
+        }
+
+        closed
+    }
+
+    /// Take the bar still being built, once there are no more samples to come.
+    pub fn finish(&mut self) -> Option<Bar> {
+        self.current.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_bars_close_when_a_later_bucket_starts() {
+        let mut builder = BarBuilder::new(BarKind::Time, 1_000.0);
+
+        assert!(builder.push("BTCUSDT", 1_200, 10.0, 1.0).is_none());
+        assert!(builder.push("BTCUSDT", 1_999, 12.0, 2.0).is_none());
+        let bar = builder.push("BTCUSDT", 2_000, 11.0, 1.0).unwrap();
+
+        assert_eq!((bar.open_time, bar.close_time), (1_000, 1_999));
+        assert_eq!((bar.open, bar.high, bar.low, bar.close), (10.0, 12.0, 10.0, 12.0));
+        assert_eq!((bar.volume, bar.quote_volume, bar.n_ticks), (3.0, 34.0, 2));
+
+        let open = builder.finish().unwrap();
+        assert_eq!((open.open_time, open.close_time, open.n_ticks), (2_000, 2_999, 1));
+        assert!(builder.finish().is_none());
+    }
+
+    #[test]
+    fn time_bars_skip_empty_buckets() {
+        let mut builder = BarBuilder::new(BarKind::Time, 1_000.0);
+
+        builder.push("BTCUSDT", 500, 10.0, 1.0);
+        let bar = builder.push("BTCUSDT", 5_500, 11.0, 1.0).unwrap();
+
+        assert_eq!(bar.open_time, 0);
+        assert_eq!(builder.finish().unwrap().open_time, 5_000);
+    }
+
+    #[test]
+    fn tick_bars_close_on_the_last_sample() {
+        let mut builder = BarBuilder::new(BarKind::Tick, 2.0);
+
+        assert!(builder.push("BTCUSDT", 10, 10.0, 1.0).is_none());
+        let bar = builder.push("BTCUSDT", 20, 11.0, 1.0).unwrap();
+
+        assert_eq!((bar.open_time, bar.close_time, bar.n_ticks), (10, 20, 2));
+        assert!(builder.finish().is_none());
+    }
+
+    #[test]
+    fn volume_bars_overshoot_rather_than_split_a_sample() {
+        let mut builder = BarBuilder::new(BarKind::Volume, 2.0);
+
+        assert!(builder.push("BTCUSDT", 10, 10.0, 1.5).is_none());
+        let bar = builder.push("BTCUSDT", 20, 10.0, 1.5).unwrap();
+
+        assert_eq!(bar.volume, 3.0);
+        assert!(builder.finish().is_none());
+    }
+
+    #[test]
+    fn dollar_bars_count_quote_volume() {
+        let mut builder = BarBuilder::new(BarKind::Dollar, 100.0);
+
+        assert!(builder.push("BTCUSDT", 10, 10.0, 5.0).is_none());
+        assert!(builder.push("BTCUSDT", 20, 20.0, 2.0).is_none());
+        let bar = builder.push("BTCUSDT", 30, 20.0, 0.5).unwrap();
+
+        assert_eq!((bar.quote_volume, bar.n_ticks), (100.0, 3));
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use binance::model::Kline;
+
+use crate::bar::Bar;
+
+// volumes are summed in a different order to the exchange's, so allow for rounding
+const VOLUME_TOLERANCE: f64 = 1e-9;
+
+fn volume_matches(a: f64, b: f64) -> bool {
+    (a - b).abs() <= VOLUME_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Pairs time bars built from the tape with the exchange's own klines for the
+/// same bucket and reports any that disagree.
+pub struct KlineCheck {
+    interval: u64,
+    // first trade time per symbol, buckets before it were only partly recorded
+    first_trade: HashMap<String, u64>,
+    bars: HashMap<(String, u64), Bar>,
+    klines: HashMap<(String, u64), Kline>,
+    pub n_checked: usize,
+    pub n_mismatched: usize,
+}
+
+impl KlineCheck {
+    pub fn new(interval: u64) -> Self {
+        Self {
+            interval,
+            first_trade: HashMap::new(),
+            bars: HashMap::new(),
+            klines: HashMap::new(),
+            n_checked: 0,
+            n_mismatched: 0,
+        }
+    }
+
+    pub fn observe_trade(&mut self, symbol: &str, time: u64) {
+        self.first_trade.entry(symbol.to_string()).or_insert(time);
+    }
+
+    pub fn add_bar(&mut self, bar: &Bar) {
+        let key = (bar.symbol.clone(), bar.open_time);
+        match self.klines.remove(&key) {
+            Some(kline) => self.compare(bar, &kline),
+            None => {
+                self.bars.insert(key, bar.clone());
+            }
+        }
+    }
+
+    pub fn add_kline(&mut self, symbol: &str, kline: Kline) {
+        // only closed klines of the same interval as our bars are comparable
+        let interval = (kline.close_time - kline.open_time + 1) as u64;
+        if !kline.is_final_bar || interval != self.interval {
+            return;
+        }
+
+        let open_time = kline.open_time as u64;
+        match self.first_trade.get(symbol) {
+            Some(&first_trade) if first_trade > open_time => return,
+            None => return,
+            _ => (),
+        }
+
+        let key = (symbol.to_string(), open_time);
+        match self.bars.remove(&key) {
+            Some(bar) => self.compare(&bar, &kline),
+            None => {
+                self.klines.insert(key, kline);
+            }
+        }
+    }
+
+    fn compare(&mut self, bar: &Bar, kline: &Kline) {
+        let parse = |value: &str| value.parse::<f64>().unwrap();
+
+        self.n_checked += 1;
+        let matches = bar.open == parse(&kline.open)
+            && bar.high == parse(&kline.high)
+            && bar.low == parse(&kline.low)
+            && bar.close == parse(&kline.close)
+            && volume_matches(bar.volume, parse(&kline.volume));
+
+        if !matches {
+            self.n_mismatched += 1;
+            eprintln!(
+                "{} bar at {} differs from kline: ours o {} h {} l {} c {} v {}, exchange o {} h {} l {} c {} v {}",
+                bar.symbol,
+                bar.open_time,
+                bar.open,
+                bar.high,
+                bar.low,
+                bar.close,
+                bar.volume,
+                kline.open,
+                kline.high,
+                kline.low,
+                kline.close,
+                kline.volume
+            );
+        }
+    }
+}
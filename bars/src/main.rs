@@ -0,0 +1,194 @@
+mod bar;
+mod klines;
+
+use bar::{Bar, BarBuilder, BarKind};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, ValueEnum};
+use datatypes::reader::EventIterator;
+use datatypes::world_builder::World;
+use datatypes::EventType;
+use klines::KlineCheck;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Source {
+    /// Aggregated trades from an `interleave`d tape
+    Trades,
+    /// Mid prices from `accumulate` output
+    Mid,
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// What to read from stdin
+    #[arg(long, value_enum, default_value_t = Source::Trades)]
+    source: Source,
+
+    /// How bars are cut
+    #[arg(long, value_enum, default_value_t = BarKind::Time)]
+    kind: BarKind,
+
+    /// Bar size: a duration like 500ms, 1m or 4h for time bars, otherwise a
+    /// number of samples, base asset or quote asset
+    #[arg(long, default_value = "1m")]
+    size: String,
+
+    /// Compare time bars with the exchange's klines in the tape (recorded with
+    /// `record --klines`) and report differences on stderr
+    #[arg(long)]
+    check_klines: bool,
+}
+
+// milliseconds in a duration like 1m, bare numbers are milliseconds
+fn parse_duration(size: &str) -> Option<f64> {
+    let split = size.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(size.len());
+    let (value, unit) = size.split_at(split);
+    let value: f64 = value.parse().ok()?;
+
+    let unit = match unit {
+        "" | "ms" => 1.0,
+        "s" => 1_000.0,
+        "m" => 60_000.0,
+        "h" => 3_600_000.0,
+        "d" => 86_400_000.0,
+        _ => return None,
+    };
+    Some(value * unit)
+}
+
+fn output(bar: &Bar) {
+    // instead of println, do this to prevent broken pipe errors
+    let mut stdout = io::stdout();
+    let _ = writeln!(stdout, "{}", serde_json::to_string(bar).unwrap());
+}
+
+fn main() -> io::Result<()> {
+    let args = Args::parse();
+
+    if args.source == Source::Mid && matches!(args.kind, BarKind::Volume | BarKind::Dollar) {
+        Args::command()
+            .error(ErrorKind::ArgumentConflict, "mid prices carry no volume, use time or tick bars")
+            .exit();
+    }
+
+    if args.check_klines && (args.source != Source::Trades || args.kind != BarKind::Time) {
+        Args::command()
+            .error(ErrorKind::ArgumentConflict, "--check-klines needs time bars built from trades")
+            .exit();
+    }
+
+    let size = match args.kind {
+        BarKind::Time => parse_duration(&args.size),
+        _ => args.size.parse().ok(),
+    };
+    let size = match size {
+        Some(size) if size >= 1.0 || (size > 0.0 && args.kind != BarKind::Time) => size,
+        _ => Args::command()
+            .error(ErrorKind::InvalidValue, format!("invalid bar size {}", args.size))
+            .exit(),
+    };
+
+    let mut builders: HashMap<String, BarBuilder> = HashMap::new();
+    let mut check = args.check_klines.then(|| KlineCheck::new(size as u64));
+
+    let stdin = io::stdin();
+    match args.source {
+        Source::Trades => {
+            for event in EventIterator::new(stdin.lock()) {
+                let event = event?;
+
+                match event.event {
+                    EventType::Trade(trade) => {
+                        let price: f64 = trade.price.parse().unwrap();
+                        let qty: f64 = trade.qty.parse().unwrap();
+
+                        if let Some(check) = check.as_mut() {
+                            check.observe_trade(&event.symbol, trade.trade_order_time);
+                        }
+
+                        let builder = builders
+                            .entry(event.symbol.clone())
+                            .or_insert_with(|| BarBuilder::new(args.kind, size));
+                        if let Some(bar) = builder.push(&event.symbol, trade.trade_order_time, price, qty) {
+                            if let Some(check) = check.as_mut() {
+                                check.add_bar(&bar);
+                            }
+                            output(&bar);
+                        }
+                    }
+                    EventType::Kline(kline) => {
+                        if let Some(check) = check.as_mut() {
+                            check.add_kline(&event.symbol, kline.kline);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Source::Mid => {
+            // last update id per symbol, a world is emitted for every event so
+            // only books that changed give a new sample
+            let mut last_update_ids: HashMap<String, u64> = HashMap::new();
+
+            for line in stdin.lock().lines() {
+                let world: World = serde_json::from_str(&line?).unwrap();
+
+                for (symbol, ob) in &world.order_books {
+                    if last_update_ids.insert(symbol.clone(), ob.last_update_id) == Some(ob.last_update_id) {
+                        continue;
+                    }
+
+                    let (Some(bid), Some(ask)) = (ob.bids.first(), ob.asks.first()) else {
+                        continue;
+                    };
+                    let mid = (bid.price + ask.price) / 2.0;
+
+                    let builder = builders
+                        .entry(symbol.clone())
+                        .or_insert_with(|| BarBuilder::new(args.kind, size));
+                    if let Some(bar) = builder.push(symbol, world.time, mid, 0.0) {
+                        output(&bar);
+                    }
+                }
+            }
+        }
+    }
+
+    // the bars still open weren't shown to be complete by a later sample so
+    // they aren't output, but a closed kline for the bucket proves they are
+    if let Some(mut check) = check {
+        for builder in builders.values_mut() {
+            if let Some(bar) = builder.finish() {
+                check.add_bar(&bar);
+            }
+        }
+        eprintln!("{} bars checked against klines, {} differed", check.n_checked, check.n_mismatched);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_are_in_milliseconds() {
+        assert_eq!(parse_duration("250"), Some(250.0));
+        assert_eq!(parse_duration("500ms"), Some(500.0));
+        assert_eq!(parse_duration("1.5s"), Some(1_500.0));
+        assert_eq!(parse_duration("5m"), Some(300_000.0));
+        assert_eq!(parse_duration("4h"), Some(14_400_000.0));
+        assert_eq!(parse_duration("1d"), Some(86_400_000.0));
+    }
+
+    #[test]
+    fn bad_durations_are_rejected() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("5w"), None);
+        assert_eq!(parse_duration("1m30s"), None);
+    }
+}
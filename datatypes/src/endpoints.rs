@@ -0,0 +1,84 @@
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Which Binance environment to talk to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            _ => Err(format!("unknown network {} (expected mainnet or testnet)", s)),
+        }
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+/// REST and websocket base URLs, without a trailing slash or path.
+#[derive(Clone, Debug)]
+pub struct Endpoints {
+    pub rest: String,
+    pub ws: String,
+}
+
+impl Endpoints {
+    pub fn spot(network: Network) -> Self {
+        match network {
+            Network::Mainnet => Self::new("https://api.binance.com", "wss://stream.binance.com:9443"),
+            Network::Testnet => Self::new("https://testnet.binance.vision", "wss://stream.testnet.binance.vision:9443"),
+        }
+    }
+
+    /// USDⓈ-M futures.
+    pub fn futures(network: Network) -> Self {
+        match network {
+            Network::Mainnet => Self::new("https://fapi.binance.com", "wss://fstream.binance.com"),
+            Network::Testnet => Self::new("https://testnet.binancefuture.com", "wss://fstream.binancefuture.com"),
+        }
+    }
+
+    fn new(rest: &str, ws: &str) -> Self {
+        Self {
+            rest: rest.to_string(),
+            ws: ws.to_string(),
+        }
+    }
+
+    /// Replace either URL with `{prefix}REST_ENDPOINT` or `{prefix}WS_ENDPOINT`
+    /// from the environment, e.g. to point at a local mock exchange.
+    ///
+    /// Each binary reads its own prefix, so one pointed somewhere else doesn't
+    /// drag the others along.
+    pub fn overridden_by_env(self, prefix: &str) -> Self {
+        let var = |name: &str| env::var(format!("{}{}", prefix, name)).ok();
+
+        Self {
+            rest: var("REST_ENDPOINT").unwrap_or(self.rest),
+            ws: var("WS_ENDPOINT").unwrap_or(self.ws),
+        }
+    }
+
+    /// Replace either URL with one given on the command line.
+    pub fn overridden_by(self, rest: Option<String>, ws: Option<String>) -> Self {
+        Self {
+            rest: rest.unwrap_or(self.rest).trim_end_matches('/').to_string(),
+            ws: ws.unwrap_or(self.ws).trim_end_matches('/').to_string(),
+        }
+    }
+}
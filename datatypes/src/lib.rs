@@ -1,8 +1,17 @@
 pub mod reader;
 pub mod partial_transformer;
 pub mod world_builder;
+pub mod symbol_info;
+pub mod endpoints;
+pub mod recorder_stats;
 
-use binance::model::{AccountInformation, DepthOrderBookEvent, OrderBook, OrderTradeEvent};
+pub use symbol_info::SymbolInfo;
+pub use recorder_stats::RecorderStats;
+
+use binance::model::{
+    AccountInformation, AggrTradesEvent, BookTickerEvent, DepthOrderBookEvent, KlineEvent, LiquidationEvent,
+    MarkPriceEvent, OrderBook, OrderTradeEvent,
+};
 use serde::Deserialize;
 use core::panic;
 use std::io::{BufRead, Write};
@@ -13,13 +22,177 @@ pub enum EventType {
     PartialOrderBook(DepthOrderBookEvent),
     AccountInformation(AccountInformation),
     OpenOrders(Vec<binance::model::Order>),
-    OrderTradeEvent(OrderTradeEvent),
+    OrderTradeEvent(ExecutionReport),
+    ClockOffset(ClockOffset),
+    Trade(AggrTradesEvent),
+    BookTicker(BookTickerEvent),
+    BookCheck(BookCheck),
+    MarkPrice(MarkPriceEvent),
+    FundingRate(FundingRate),
+    Liquidation(LiquidationEvent),
+    Kline(KlineEvent),
+    SymbolInfo(SymbolInfo),
+    AccountPosition(AccountPosition),
+    BalanceUpdate(BalanceUpdate),
+    StreamGap(StreamGap),
+    RecorderStats(RecorderStats),
+}
+
+// The binance crate's account and balance update events are the futures
+// shapes, these are the spot user data stream's.
+// https://developers.binance.com/docs/binance-spot-api-docs/user-data-stream
+
+/// `executionReport`: an order update, along with the commission asset (`N`)
+/// the binance crate's `OrderTradeEvent` skips.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ExecutionReport {
+    #[serde(flatten)]
+    pub report: OrderTradeEvent,
+    // null until the order trades, and missing from tapes recorded before it was kept
+    #[serde(rename = "N", default)]
+    pub commission_asset: Option<String>,
+}
+
+/// `outboundAccountPosition`: the new free and locked amounts of every asset
+/// an account change touched.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct AccountPosition {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "u")]
+    pub last_update_time: u64,
+    #[serde(rename = "B")]
+    pub balances: Vec<PositionBalance>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct PositionBalance {
+    #[serde(rename = "a")]
+    pub asset: String,
+    #[serde(rename = "f")]
+    pub free: String,
+    #[serde(rename = "l")]
+    pub locked: String,
+}
+
+/// `balanceUpdate`: a deposit, withdrawal or transfer changing an asset's free amount.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct BalanceUpdate {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "a")]
+    pub asset: String,
+    #[serde(rename = "d")]
+    pub delta: String,
+    #[serde(rename = "T")]
+    pub clear_time: u64,
+}
+
+/// A stretch of time, in milliseconds, in which a stream was down and events
+/// may be missing from the tape.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct StreamGap {
+    pub from: u64,
+    pub to: u64,
+    pub reason: String,
+}
+
+/// The funding rate applied to a perpetual at a funding time.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+pub struct FundingRate {
+    pub funding_rate: f64,
+    pub funding_time: u64,
+    pub mark_price: f64,
+}
+
+/// One measurement of the local clock against the exchange's server time.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+pub struct ClockOffset {
+    // exchange time minus local time at the midpoint of the request, in milliseconds
+    pub offset: f64,
+    pub round_trip: f64,
+    pub server_time: u64,
+}
+
+/// Top of a reconstructed book compared against the exchange's book ticker at
+/// the same update id, see [`partial_transformer::PartialTransformer::validating`].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BookCheck {
+    pub update_id: u64,
+    pub ticker_update_id: u64,
+    // (price, qty), None if that side of the book is empty
+    pub book_bid: Option<(f64, f64)>,
+    pub book_ask: Option<(f64, f64)>,
+    pub ticker_bid: (f64, f64),
+    pub ticker_ask: (f64, f64),
+    pub diverged: bool,
+}
+
+impl EventType {
+    /// The variant's name, as it appears in the tape.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventType::FullOrderBook(_) => "FullOrderBook",
+            EventType::PartialOrderBook(_) => "PartialOrderBook",
+            EventType::AccountInformation(_) => "AccountInformation",
+            EventType::OpenOrders(_) => "OpenOrders",
+            EventType::OrderTradeEvent(_) => "OrderTradeEvent",
+            EventType::ClockOffset(_) => "ClockOffset",
+            EventType::Trade(_) => "Trade",
+            EventType::BookTicker(_) => "BookTicker",
+            EventType::BookCheck(_) => "BookCheck",
+            EventType::MarkPrice(_) => "MarkPrice",
+            EventType::FundingRate(_) => "FundingRate",
+            EventType::Liquidation(_) => "Liquidation",
+            EventType::Kline(_) => "Kline",
+            EventType::SymbolInfo(_) => "SymbolInfo",
+            EventType::AccountPosition(_) => "AccountPosition",
+            EventType::BalanceUpdate(_) => "BalanceUpdate",
+            EventType::StreamGap(_) => "StreamGap",
+            EventType::RecorderStats(_) => "RecorderStats",
+        }
+    }
+
+    /// The exchange's own timestamp for the event in milliseconds, if it carries one.
+    pub fn exchange_time(&self) -> Option<u64> {
+        match self {
+            EventType::PartialOrderBook(dob) => Some(dob.event_time),
+            EventType::OrderTradeEvent(ote) => Some(ote.report.event_time),
+            EventType::ClockOffset(clock) => Some(clock.server_time),
+            EventType::Trade(trade) => Some(trade.event_time),
+            EventType::MarkPrice(mark) => Some(mark.event_time),
+            EventType::FundingRate(funding) => Some(funding.funding_time),
+            EventType::Liquidation(liquidation) => Some(liquidation.event_time),
+            EventType::Kline(kline) => Some(kline.event_time),
+            EventType::AccountPosition(position) => Some(position.event_time),
+            EventType::BalanceUpdate(update) => Some(update.event_time),
+            EventType::FullOrderBook(_)
+            | EventType::AccountInformation(_)
+            | EventType::OpenOrders(_)
+            | EventType::BookTicker(_)
+            | EventType::BookCheck(_)
+            | EventType::SymbolInfo(_)
+            | EventType::StreamGap(_)
+            | EventType::RecorderStats(_) => None,
+        }
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct Event {
     pub symbol: String,
+    // local receive time in milliseconds, used to order the tape
     pub receive_time: u64,
+    // local receive time in nanoseconds, missing from older tapes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receive_time_ns: Option<u64>,
+    // exchange event time in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange_time: Option<u64>,
     pub event: EventType,
 }
 
@@ -28,9 +201,38 @@ impl Event {
         Self {
             symbol,
             receive_time,
+            receive_time_ns: None,
+            exchange_time: event.exchange_time(),
             event,
         }
     }
+
+    /// Build an event from a nanosecond receive time, see [`now_ns`].
+    pub fn received(symbol: String, receive_time_ns: u64, event: EventType) -> Self {
+        Self {
+            receive_time_ns: Some(receive_time_ns),
+            ..Self::new(symbol, receive_time_ns / 1_000_000, event)
+        }
+    }
+
+    /// Time from the exchange stamping the event to us receiving it, in milliseconds.
+    ///
+    /// Can be negative when the local clock is behind the exchange's.
+    pub fn feed_latency(&self) -> Option<f64> {
+        let receive_time_ms = match self.receive_time_ns {
+            Some(ns) => ns as f64 / 1_000_000.0,
+            None => self.receive_time as f64,
+        };
+        self.exchange_time.map(|exchange_time| receive_time_ms - exchange_time as f64)
+    }
+}
+
+/// Current wall clock time in nanoseconds since the unix epoch.
+pub fn now_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
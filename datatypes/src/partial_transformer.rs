@@ -1,9 +1,12 @@
-use std::{collections::HashMap, io};
-use binance::model::{DepthOrderBookEvent, OrderBook};
-use crate::{Event, EventType};
+use std::{collections::{HashMap, VecDeque}, io};
+use binance::model::{BookTickerEvent, DepthOrderBookEvent, OrderBook};
+use crate::{BookCheck, Event, EventType};
 
 
-fn update_full_order_book(ob: &mut OrderBook, dob: &DepthOrderBookEvent) {
+/// Apply a depth diff to a full book in place.
+pub fn update_full_order_book(ob: &mut OrderBook, dob: &DepthOrderBookEvent) {
+    ob.last_update_id = dob.final_update_id;
+
     // bids are in descending order
     for bid in dob.bids.iter() {
         // find index of bid in ob.bids
@@ -51,17 +54,115 @@ fn update_full_order_book(ob: &mut OrderBook, dob: &DepthOrderBookEvent) {
     }
 }
 
+#[derive(Default)]
+struct TickerQueue {
+    // tickers newer than the book, waiting for it to catch up
+    queued: VecDeque<BookTickerEvent>,
+    // newest ticker at or before the book's update id
+    reference: Option<BookTickerEvent>,
+}
+
+fn parse_level(price: &str, qty: &str) -> Option<(f64, f64)> {
+    Some((price.parse().ok()?, qty.parse().ok()?))
+}
+
+// the ticker's best bid and ask as (price, qty), None if either doesn't parse
+fn ticker_levels(ticker: &BookTickerEvent) -> Option<((f64, f64), (f64, f64))> {
+    Some((
+        parse_level(&ticker.best_bid, &ticker.best_bid_qty)?,
+        parse_level(&ticker.best_ask, &ticker.best_ask_qty)?,
+    ))
+}
+
+// The ticker is pushed on every change to the best bid or ask, so the newest
+// ticker at or before the book's update id is the exact top of book at that id.
+fn check_book(ob: &OrderBook, ticker: &BookTickerEvent) -> Option<BookCheck> {
+    let book_bid = ob.bids.first().map(|bid| (bid.price, bid.qty));
+    let book_ask = ob.asks.first().map(|ask| (ask.price, ask.qty));
+    let (ticker_bid, ticker_ask) = ticker_levels(ticker)?;
+
+    Some(BookCheck {
+        update_id: ob.last_update_id,
+        ticker_update_id: ticker.update_id,
+        book_bid,
+        book_ask,
+        ticker_bid,
+        ticker_ask,
+        diverged: book_bid != Some(ticker_bid) || book_ask != Some(ticker_ask),
+    })
+}
+
 pub struct PartialTransformer {
     event_iter: Box<dyn Iterator<Item = io::Result<Event>>>,
     order_books: HashMap<String, OrderBook>,
+    validate: bool,
+    tickers: HashMap<String, TickerQueue>,
+    pending: VecDeque<Event>,
 }
 
 impl PartialTransformer {
+    /// Turn every diff into the full book it produces.
+    ///
+    /// Diffs whose final update id the book already includes are dropped, a
+    /// snapshot can reach the tape after diffs it covers, and each book carries
+    /// the `u` of the last diff applied as its `last_update_id`.
     pub fn new(event_iter: Box<dyn Iterator<Item = io::Result<Event>>>) -> Self {
         Self {
-            event_iter: event_iter,
+            event_iter,
             order_books: HashMap::new(),
+            validate: false,
+            tickers: HashMap::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Like [`PartialTransformer::new`], but every time a book is updated its
+    /// top is checked against the recorded `BookTicker` events, and a
+    /// `BookCheck` event follows the `FullOrderBook` it was made for.
+    pub fn validating(event_iter: Box<dyn Iterator<Item = io::Result<Event>>>) -> Self {
+        Self {
+            validate: true,
+            ..Self::new(event_iter)
+        }
+    }
+
+    fn queue_ticker(&mut self, symbol: &str, ticker: &BookTickerEvent) {
+        // a damaged ticker can't be checked against, don't let it stand in for a good one
+        if ticker_levels(ticker).is_none() {
+            eprintln!("{} ticker {} has an unreadable price or qty, skipping it", symbol, ticker.update_id);
+            return;
         }
+
+        // a ticker the book has already moved past can't be lined up with it any more
+        if let Some(ob) = self.order_books.get(symbol) {
+            if ticker.update_id <= ob.last_update_id {
+                return;
+            }
+        }
+
+        let queue = self.tickers.entry(symbol.to_string()).or_default();
+        queue.queued.push_back(ticker.clone());
+    }
+
+    fn check(&mut self, ev: &Event) -> Option<Event> {
+        let ob = self.order_books.get(&ev.symbol)?;
+        let queue = self.tickers.get_mut(&ev.symbol)?;
+
+        while let Some(ticker) = queue.queued.front() {
+            if ticker.update_id > ob.last_update_id {
+                break;
+            }
+            queue.reference = queue.queued.pop_front();
+        }
+
+        let check = check_book(ob, queue.reference.as_ref()?)?;
+        Some(Event {
+            symbol: ev.symbol.clone(),
+            receive_time: ev.receive_time,
+            receive_time_ns: ev.receive_time_ns,
+            exchange_time: ev.exchange_time,
+            event: EventType::BookCheck(check),
+        })
     }
 }
 
@@ -69,6 +170,10 @@ impl Iterator for PartialTransformer {
     type Item = io::Result<Event>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(ev) = self.pending.pop_front() {
+            return Some(Ok(ev));
+        }
+
         let e = self.event_iter.next();
 
         if let Some(Ok(ref ev)) = e {
@@ -78,22 +183,38 @@ impl Iterator for PartialTransformer {
                 }
                 EventType::PartialOrderBook(ref dob) => {
                     if let Some(ob) = self.order_books.get_mut(&ev.symbol) {
+                        // diffs the snapshot already includes would undo newer changes
+                        if dob.final_update_id <= ob.last_update_id {
+                            return self.next();
+                        }
                         update_full_order_book(ob, dob);
                     }
                 }
+                EventType::BookTicker(ref ticker) if self.validate => {
+                    self.queue_ticker(&ev.symbol, ticker);
+                    return Some(Ok(ev.clone()));
+                }
                 _ => {
                     return Some(Ok(ev.clone()));
                 }
             }
 
+            if self.validate {
+                if let Some(check) = self.check(ev) {
+                    self.pending.push_back(check);
+                }
+            }
+
             // if we have a full order book for this symbol, we can create a new event
 
-            if let Some(ob) = self.order_books.get(&ev.symbol) {      
-                Some(Ok(Event::new(
-                    ev.symbol.clone(),
-                    ev.receive_time,
-                    EventType::FullOrderBook(ob.clone()),
-                )))
+            if let Some(ob) = self.order_books.get(&ev.symbol) {
+                Some(Ok(Event {
+                    symbol: ev.symbol.clone(),
+                    receive_time: ev.receive_time,
+                    receive_time_ns: ev.receive_time_ns,
+                    exchange_time: ev.exchange_time,
+                    event: EventType::FullOrderBook(ob.clone()),
+                }))
             } else {
                 self.next()
             }
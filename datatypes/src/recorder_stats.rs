@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+/// Upper bounds of the latency buckets in milliseconds, anything slower
+/// lands in a last, unbounded bucket.
+pub const LATENCY_BUCKETS: [f64; 10] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0];
+
+/// Counts of feed latencies, see [`crate::Event::feed_latency`].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct LatencyHistogram {
+    // one more than LATENCY_BUCKETS, the last for everything slower
+    pub counts: Vec<u64>,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; LATENCY_BUCKETS.len() + 1],
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency_ms: f64) {
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.counts[bucket] += 1;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile, infinite if it's
+    /// the last one and None if nothing was recorded.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+
+        let rank = (q * total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(LATENCY_BUCKETS.get(bucket).copied().unwrap_or(f64::INFINITY));
+            }
+        }
+        Some(f64::INFINITY)
+    }
+}
+
+/// What `record` has been doing, written to the tape every few seconds.
+///
+/// Per symbol counts and the latency histogram cover the last `interval_ms`,
+/// everything else is a running total since the recorder started.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct RecorderStats {
+    pub start_time: u64,
+    pub interval_ms: u64,
+    // stream messages received per symbol
+    pub messages: BTreeMap<String, u64>,
+    // REST depth snapshots taken per symbol
+    pub full_books: BTreeMap<String, u64>,
+    pub latency: LatencyHistogram,
+    pub bytes_written: u64,
+    pub n_gaps: u64,
+    pub n_reconnects: u64,
+    pub n_rest_errors: u64,
+    pub n_rate_limited: u64,
+    pub n_banned: u64,
+    pub used_weight: u32,
+    pub weight_limit: u32,
+}
@@ -0,0 +1,104 @@
+/// A symbol's trading rules as published in the exchange info, see
+/// <https://developers.binance.com/docs/binance-spot-api-docs/filters>.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    // TRADING, BREAK, HALT...
+    pub status: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub tick_size: f64,
+    pub min_price: f64,
+    pub max_price: f64,
+    pub step_size: f64,
+    pub min_qty: f64,
+    pub max_qty: f64,
+    // smallest price * qty accepted, if the symbol has a notional filter
+    pub min_notional: Option<f64>,
+    // decimal places in the tick and step size, for fixed-point prices and quantities
+    pub price_decimals: u32,
+    pub qty_decimals: u32,
+}
+
+/// Why an order would be rejected under a symbol's rules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleViolation {
+    NotTrading,
+    PriceOutOfRange,
+    PriceNotOnTick,
+    QtyOutOfRange,
+    QtyNotOnStep,
+    BelowMinNotional,
+}
+
+/// Number of decimal places in a filter value like "0.01000000".
+pub fn decimals(value: &str) -> u32 {
+    match value.split_once('.') {
+        Some((_, fraction)) => fraction.trim_end_matches('0').len() as u32,
+        None => 0,
+    }
+}
+
+// a filter value of zero disables that part of the filter
+fn on_grid(value: f64, min: f64, step: f64) -> bool {
+    if step == 0.0 {
+        return true;
+    }
+    let steps = (value - min) / step;
+    (steps - steps.round()).abs() < 1e-9
+}
+
+impl SymbolInfo {
+    /// Round a price to the nearest tick.
+    pub fn round_price(&self, price: f64) -> f64 {
+        if self.tick_size == 0.0 {
+            return price;
+        }
+        let rounded = (price / self.tick_size).round() * self.tick_size;
+        let scale = 10f64.powi(self.price_decimals as i32);
+        (rounded * scale).round() / scale
+    }
+
+    /// Round a quantity down to a whole number of steps.
+    pub fn round_qty(&self, qty: f64) -> f64 {
+        if self.step_size == 0.0 {
+            return qty;
+        }
+        // nudge up so a qty already on the step isn't floored to the one below
+        let rounded = (qty / self.step_size + 1e-9).floor() * self.step_size;
+        let scale = 10f64.powi(self.qty_decimals as i32);
+        (rounded * scale).round() / scale
+    }
+
+    /// A price as an integer number of the smallest price unit.
+    pub fn price_to_fixed(&self, price: f64) -> i64 {
+        (price * 10f64.powi(self.price_decimals as i32)).round() as i64
+    }
+
+    /// A quantity as an integer number of the smallest quantity unit.
+    pub fn qty_to_fixed(&self, qty: f64) -> i64 {
+        (qty * 10f64.powi(self.qty_decimals as i32)).round() as i64
+    }
+
+    /// Check a limit order against the price, lot size and notional filters.
+    pub fn check_order(&self, price: f64, qty: f64) -> Result<(), RuleViolation> {
+        if self.status != "TRADING" {
+            return Err(RuleViolation::NotTrading);
+        }
+        if (self.min_price != 0.0 && price < self.min_price) || (self.max_price != 0.0 && price > self.max_price) {
+            return Err(RuleViolation::PriceOutOfRange);
+        }
+        if !on_grid(price, self.min_price, self.tick_size) {
+            return Err(RuleViolation::PriceNotOnTick);
+        }
+        if qty < self.min_qty || (self.max_qty != 0.0 && qty > self.max_qty) {
+            return Err(RuleViolation::QtyOutOfRange);
+        }
+        if !on_grid(qty, self.min_qty, self.step_size) {
+            return Err(RuleViolation::QtyNotOnStep);
+        }
+        if self.min_notional.is_some_and(|min_notional| price * qty < min_notional) {
+            return Err(RuleViolation::BelowMinNotional);
+        }
+        Ok(())
+    }
+}
@@ -1,20 +1,109 @@
-use std::{collections::HashMap, fmt::{self, Display}, io, ops::Deref, sync::Arc};
-use binance::model::{AccountInformation, DepthOrderBookEvent, OrderBook};
-use crate::{partial_transformer::PartialTransformer, Event, EventType};
+use std::{collections::{HashMap, VecDeque}, io};
+use binance::model::{AccountInformation, AggrTradesEvent, Balance, OrderBook};
+use crate::{
+    partial_transformer::PartialTransformer, AccountPosition, BalanceUpdate, BookCheck, Event, EventType, SymbolInfo,
+};
+
+// trades older than this (in ms) drop out of the rolling volume
+pub const ROLLING_VOLUME_WINDOW: u64 = 60_000;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct TradeStats {
+    pub last_price: f64,
+    pub last_qty: f64,
+    pub last_trade_time: u64,
+    // base asset volume traded over the rolling window
+    pub volume: f64,
+    // the part of `volume` where the taker was the buyer
+    pub buy_volume: f64,
+    // (trade time, qty, taker bought) for every trade still in the window
+    #[serde(skip)]
+    window: VecDeque<(u64, f64, bool)>,
+}
+
+impl TradeStats {
+    fn add_trade(&mut self, trade: &AggrTradesEvent) {
+        let price: f64 = trade.price.parse().unwrap();
+        let qty: f64 = trade.qty.parse().unwrap();
+        // the maker is the buyer when the taker sold into the bid
+        let taker_bought = !trade.is_buyer_maker;
+
+        self.last_price = price;
+        self.last_qty = qty;
+        self.last_trade_time = trade.trade_order_time;
+
+        self.window.push_back((trade.trade_order_time, qty, taker_bought));
+        self.volume += qty;
+        if taker_bought {
+            self.buy_volume += qty;
+        }
+
+        self.expire(trade.trade_order_time);
+    }
+
+    // drop trades that fell out of the window as of `now`, so a symbol that
+    // stops trading doesn't keep its last window's volume
+    fn expire(&mut self, now: u64) {
+        let cutoff = now.saturating_sub(ROLLING_VOLUME_WINDOW);
+        while let Some(&(time, qty, taker_bought)) = self.window.front() {
+            if time >= cutoff {
+                break;
+            }
+            self.window.pop_front();
+            self.volume -= qty;
+            if taker_bought {
+                self.buy_volume -= qty;
+            }
+        }
+
+        // don't leave float residue behind once everything has expired
+        if self.window.is_empty() {
+            self.volume = 0.0;
+            self.buy_volume = 0.0;
+        }
+    }
+}
+
+/// Running accuracy of a reconstructed book against the exchange's book ticker.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct BookHealth {
+    pub last_check: BookCheck,
+    pub n_checks: usize,
+    pub n_diverged: usize,
+}
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct World {
+    // receive time of the last event applied, in milliseconds
+    #[serde(default)]
+    pub time: u64,
     pub order_books: HashMap<String, OrderBook>,
     pub account_information: Option<AccountInformation>,
-    pub open_orders: Vec<binance::model::Order>
+    // `u` of the last account position applied, balance updates cleared at or
+    // before it are already reflected in the balances
+    #[serde(default)]
+    pub account_update_time: u64,
+    pub open_orders: Vec<binance::model::Order>,
+    #[serde(default)]
+    pub trades: HashMap<String, TradeStats>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub book_health: HashMap<String, BookHealth>,
+    // trading rules per symbol as of the last exchange info fetch
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub symbol_info: HashMap<String, SymbolInfo>,
 }
 
 impl World {
     pub fn new() -> Self {
         Self {
+            time: 0,
             order_books: HashMap::new(),
             account_information: None,
-            open_orders: Vec::new()
+            account_update_time: 0,
+            open_orders: Vec::new(),
+            trades: HashMap::new(),
+            book_health: HashMap::new(),
+            symbol_info: HashMap::new(),
         }
     }
 
@@ -23,14 +112,111 @@ impl World {
         self.order_books.insert(symbol, ob);
     }
 
-    pub fn update_account_information(&mut self, account: AccountInformation) {
+    /// Replace the account with a REST snapshot taken at `time` (ms).
+    ///
+    /// Balance updates cleared before the snapshot are already in it, so they
+    /// count as applied from here on.
+    pub fn update_account_information(&mut self, account: AccountInformation, time: u64) {
         self.account_information = Some(account);
+        self.account_update_time = self.account_update_time.max(time);
+    }
+
+    /// Overwrite the balances an account position reports.
+    ///
+    /// Balances are only tracked on top of an account snapshot, without one
+    /// there is nothing to update.
+    pub fn update_account_position(&mut self, position: &AccountPosition) {
+        let Some(account) = self.account_information.as_mut() else {
+            return;
+        };
+
+        for update in &position.balances {
+            match account.balances.iter_mut().find(|balance| balance.asset == update.asset) {
+                Some(balance) => {
+                    balance.free = update.free.clone();
+                    balance.locked = update.locked.clone();
+                }
+                None => account.balances.push(Balance {
+                    asset: update.asset.clone(),
+                    free: update.free.clone(),
+                    locked: update.locked.clone(),
+                }),
+            }
+        }
+
+        self.account_update_time = self.account_update_time.max(position.last_update_time);
+    }
+
+    /// Apply a deposit, withdrawal or transfer to the asset's free balance.
+    pub fn update_balance(&mut self, update: &BalanceUpdate) {
+        // the account position that follows a balance update may have arrived first
+        if update.clear_time <= self.account_update_time {
+            return;
+        }
+        let Some(account) = self.account_information.as_mut() else {
+            return;
+        };
+        let Ok(delta) = update.delta.parse::<f64>() else {
+            eprintln!("Skipping {} balance update with an unreadable delta {:?}", update.asset, update.delta);
+            return;
+        };
+
+        match account.balances.iter_mut().find(|balance| balance.asset == update.asset) {
+            Some(balance) => {
+                let Ok(free) = balance.free.parse::<f64>() else {
+                    eprintln!("Skipping {} balance update, the free balance {:?} is unreadable", update.asset, balance.free);
+                    return;
+                };
+                balance.free = format!("{:.8}", free + delta);
+            }
+            None => account.balances.push(Balance {
+                asset: update.asset.clone(),
+                free: format!("{:.8}", delta),
+                locked: format!("{:.8}", 0.0),
+            }),
+        }
     }
 
     pub fn update_open_orders(&mut self, orders: Vec<binance::model::Order>) {
         // insert or update open orders
         self.open_orders = orders;
     }
+
+    /// Move the world's clock on, expiring trades from every rolling window.
+    pub fn advance_time(&mut self, time: u64) {
+        self.time = time;
+        for stats in self.trades.values_mut() {
+            stats.expire(time);
+        }
+    }
+
+    pub fn update_trades(&mut self, symbol: String, trade: &AggrTradesEvent) {
+        self.trades.entry(symbol).or_default().add_trade(trade);
+    }
+
+    pub fn update_symbol_info(&mut self, symbol: String, info: SymbolInfo) {
+        self.symbol_info.insert(symbol, info);
+    }
+
+    pub fn update_book_health(&mut self, symbol: String, check: BookCheck) {
+        let health = self.book_health.entry(symbol).or_insert(BookHealth {
+            last_check: check,
+            n_checks: 0,
+            n_diverged: 0,
+        });
+
+        health.last_check = check;
+        health.n_checks += 1;
+        if check.diverged {
+            health.n_diverged += 1;
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub struct WorldBuilder {
@@ -47,6 +233,17 @@ impl WorldBuilder {
             world: World::new()
         }
     }
+
+    /// A builder that also keeps `World::book_health` up to date, see
+    /// [`PartialTransformer::validating`].
+    pub fn validating(event_iter: Box<dyn Iterator<Item = io::Result<Event>>>) -> Self {
+        let partial_transformer = PartialTransformer::validating(event_iter);
+
+        Self {
+            stream: Box::new(partial_transformer),
+            world: World::new()
+        }
+    }
 }
 
 impl Iterator for WorldBuilder {
@@ -60,6 +257,9 @@ impl Iterator for WorldBuilder {
         };
         
         let symbol = event.as_ref().unwrap().symbol.clone();
+        let receive_time = event.as_ref().unwrap().receive_time;
+        let exchange_time = event.as_ref().unwrap().exchange_time;
+        self.world.advance_time(receive_time);
 
         match event.unwrap().event {
             EventType::PartialOrderBook(_) => panic!("Partial order book found in final event stream"),
@@ -67,12 +267,29 @@ impl Iterator for WorldBuilder {
                 self.world.update_order_book(symbol, ob);
             },
             EventType::AccountInformation(account) => {
-                self.world.update_account_information(account);
+                // the snapshot has no time of its own, it's at least as new as the
+                // moment it was received
+                self.world.update_account_information(account, exchange_time.unwrap_or(receive_time));
             },
             EventType::OpenOrders(orders) => {
                 self.world.update_open_orders(orders);
             }
-            (_) => ()
+            EventType::Trade(trade) => {
+                self.world.update_trades(symbol, &trade);
+            }
+            EventType::BookCheck(check) => {
+                self.world.update_book_health(symbol, check);
+            }
+            EventType::AccountPosition(position) => {
+                self.world.update_account_position(&position);
+            }
+            EventType::BalanceUpdate(update) => {
+                self.world.update_balance(&update);
+            }
+            EventType::SymbolInfo(info) => {
+                self.world.update_symbol_info(symbol, info);
+            }
+            _ => ()
         };
 
         Some(Ok(self.world.clone()))
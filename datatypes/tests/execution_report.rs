@@ -0,0 +1,47 @@
+use datatypes::{Event, EventType};
+use serde_json::json;
+
+fn report(commission_asset: Option<&str>) -> serde_json::Value {
+    let mut report = json!({
+        "e": "executionReport", "E": 1000, "s": "BTCUSDT", "c": "client", "S": "BUY", "o": "LIMIT",
+        "f": "GTC", "q": "1.00000000", "p": "100.00000000", "x": "TRADE", "X": "FILLED", "r": "NONE",
+        "i": 7, "l": "1.00000000", "z": "1.00000000", "L": "100.00000000", "n": "0.00100000",
+        "T": 1000, "t": 3, "m": false
+    });
+    if let Some(asset) = commission_asset {
+        report["N"] = json!(asset);
+    }
+    report
+}
+
+fn commission_asset(event: &Event) -> Option<String> {
+    match &event.event {
+        EventType::OrderTradeEvent(update) => update.commission_asset.clone(),
+        other => panic!("expected an order update, got {}", other.name()),
+    }
+}
+
+#[test]
+fn commission_asset_survives_the_tape() {
+    let event = Event::received(
+        "account".to_string(),
+        1_000_000_000,
+        EventType::OrderTradeEvent(serde_json::from_value(report(Some("BNB"))).unwrap()),
+    );
+    let line = serde_json::to_string(&event).unwrap();
+    let read: Event = serde_json::from_str(&line).unwrap();
+
+    assert_eq!(commission_asset(&read), Some("BNB".to_string()));
+    match read.event {
+        EventType::OrderTradeEvent(update) => assert_eq!(update.report.order_id, 7),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn tapes_without_the_commission_asset_still_read() {
+    let line = json!({"symbol": "account", "receive_time": 1000, "event": {"OrderTradeEvent": report(None)}});
+    let read: Event = serde_json::from_value(line).unwrap();
+
+    assert_eq!(commission_asset(&read), None);
+}
@@ -0,0 +1,162 @@
+use datatypes::partial_transformer::PartialTransformer;
+use datatypes::{BookCheck, Event, EventType};
+use serde_json::json;
+
+fn event(value: serde_json::Value) -> Event {
+    serde_json::from_value(value).unwrap()
+}
+
+fn full_book(time: u64, last_update_id: u64) -> Event {
+    event(json!({
+        "symbol": "BTCUSDT",
+        "receive_time": time,
+        "event": {"FullOrderBook": {"lastUpdateId": last_update_id, "bids": [["100.0", "1.0"]], "asks": [["101.0", "1.0"]]}}
+    }))
+}
+
+fn diff(time: u64, first: u64, last: u64, bid_qty: &str) -> Event {
+    event(json!({
+        "symbol": "BTCUSDT",
+        "receive_time": time,
+        "event": {"PartialOrderBook": {
+            "e": "depthUpdate", "E": time, "s": "BTCUSDT", "U": first, "u": last,
+            "b": [["100.0", bid_qty]], "a": []
+        }}
+    }))
+}
+
+fn books(events: Vec<Event>) -> Vec<(u64, f64)> {
+    PartialTransformer::new(Box::new(events.into_iter().map(Ok)))
+        .map(|event| match event.unwrap().event {
+            EventType::FullOrderBook(ob) => (ob.last_update_id, ob.bids[0].qty),
+            other => panic!("unexpected {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn diffs_carry_their_update_id_into_the_book() {
+    let books = books(vec![full_book(0, 100), diff(1, 101, 105, "2.0"), diff(2, 106, 110, "3.0")]);
+
+    assert_eq!(books, vec![(100, 1.0), (105, 2.0), (110, 3.0)]);
+}
+
+// a snapshot that landed after diffs it already includes, e.g. one held up by
+// the rate limiter, must not have those diffs applied over it again
+#[test]
+fn diffs_the_book_already_includes_are_dropped() {
+    let books = books(vec![
+        full_book(0, 100),
+        diff(1, 101, 105, "2.0"),
+        full_book(2, 110),
+        diff(3, 106, 110, "9.0"),
+        diff(4, 111, 115, "4.0"),
+    ]);
+
+    assert_eq!(books, vec![(100, 1.0), (105, 2.0), (110, 1.0), (115, 4.0)]);
+}
+
+fn ticker(time: u64, update_id: u64, bid: (&str, &str), ask: (&str, &str)) -> Event {
+    event(json!({
+        "symbol": "BTCUSDT",
+        "receive_time": time,
+        "event": {"BookTicker": {"u": update_id, "s": "BTCUSDT", "b": bid.0, "B": bid.1, "a": ask.0, "A": ask.1}}
+    }))
+}
+
+fn checks(events: Vec<Event>) -> Vec<BookCheck> {
+    PartialTransformer::validating(Box::new(events.into_iter().map(Ok)))
+        .filter_map(|event| match event.unwrap().event {
+            EventType::BookCheck(check) => Some(check),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn book_matching_the_ticker_checks_out() {
+    let checks = checks(vec![
+        full_book(0, 100),
+        ticker(1, 102, ("100.0", "2.0"), ("101.0", "1.0")),
+        diff(2, 101, 102, "2.0"),
+    ]);
+
+    assert_eq!(
+        checks,
+        vec![BookCheck {
+            update_id: 102,
+            ticker_update_id: 102,
+            book_bid: Some((100.0, 2.0)),
+            book_ask: Some((101.0, 1.0)),
+            ticker_bid: (100.0, 2.0),
+            ticker_ask: (101.0, 1.0),
+            diverged: false,
+        }]
+    );
+}
+
+#[test]
+fn book_disagreeing_with_the_ticker_diverged() {
+    let checks = checks(vec![
+        full_book(0, 100),
+        ticker(1, 102, ("100.0", "3.0"), ("101.0", "1.0")),
+        diff(2, 101, 102, "2.0"),
+    ]);
+
+    assert_eq!(
+        checks,
+        vec![BookCheck {
+            update_id: 102,
+            ticker_update_id: 102,
+            book_bid: Some((100.0, 2.0)),
+            book_ask: Some((101.0, 1.0)),
+            ticker_bid: (100.0, 3.0),
+            ticker_ask: (101.0, 1.0),
+            diverged: true,
+        }]
+    );
+}
+
+// a ticker ahead of the book waits for the book to catch up, until then the
+// book is checked against the newest ticker at or before its update id
+#[test]
+fn tickers_ahead_of_the_book_wait_for_it() {
+    let checks = checks(vec![
+        full_book(0, 100),
+        ticker(1, 101, ("100.0", "1.5"), ("101.0", "1.0")),
+        ticker(2, 105, ("100.0", "4.0"), ("101.0", "1.0")),
+        diff(3, 101, 101, "1.5"),
+        diff(4, 102, 103, "3.0"),
+        diff(5, 104, 105, "4.0"),
+    ]);
+
+    let ids = checks
+        .iter()
+        .map(|check| (check.update_id, check.ticker_update_id, check.diverged))
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![(101, 101, false), (103, 101, true), (105, 105, false)]);
+}
+
+#[test]
+fn no_check_before_a_ticker_has_caught_up() {
+    let checks = checks(vec![
+        full_book(0, 100),
+        ticker(1, 110, ("100.0", "2.0"), ("101.0", "1.0")),
+        diff(2, 101, 102, "2.0"),
+    ]);
+
+    assert!(checks.is_empty());
+}
+
+#[test]
+fn unreadable_tickers_are_skipped() {
+    let checks = checks(vec![
+        full_book(0, 100),
+        ticker(1, 101, ("100.0", "2.0"), ("101.0", "1.0")),
+        ticker(2, 102, ("10", ""), ("101.0", "1.0")),
+        diff(3, 101, 102, "2.0"),
+    ]);
+
+    assert_eq!(checks.len(), 1);
+    assert_eq!((checks[0].update_id, checks[0].ticker_update_id), (102, 101));
+}
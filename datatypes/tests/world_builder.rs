@@ -0,0 +1,63 @@
+use datatypes::world_builder::{World, WorldBuilder};
+use datatypes::Event;
+use serde_json::json;
+
+fn event(time: u64, event: serde_json::Value) -> Event {
+    serde_json::from_value(json!({"symbol": "account", "receive_time": time, "event": event})).unwrap()
+}
+
+fn snapshot(time: u64, btc_free: &str) -> Event {
+    event(time, json!({"AccountInformation": {
+        "makerCommission": 10, "takerCommission": 10, "buyerCommission": 0, "sellerCommission": 0,
+        "canTrade": true, "canWithdraw": true, "canDeposit": true,
+        "balances": [{"asset": "BTC", "free": btc_free, "locked": "0.00000000"}]
+    }}))
+}
+
+fn position(time: u64, btc_free: &str) -> Event {
+    event(time, json!({"AccountPosition": {
+        "e": "outboundAccountPosition", "E": time, "u": time,
+        "B": [{"a": "BTC", "f": btc_free, "l": "0.00000000"}]
+    }}))
+}
+
+fn balance_update(time: u64, clear_time: u64, delta: &str) -> Event {
+    event(time, json!({"BalanceUpdate": {"e": "balanceUpdate", "E": clear_time, "a": "BTC", "d": delta, "T": clear_time}}))
+}
+
+fn btc_free(events: Vec<Event>) -> String {
+    let world: World = WorldBuilder::new(Box::new(events.into_iter().map(Ok))).last().unwrap().unwrap();
+    let account = world.account_information.unwrap();
+    account.balances.iter().find(|balance| balance.asset == "BTC").unwrap().free.clone()
+}
+
+// a deposit cleared before the snapshot is in it, even when its own event
+// reaches the tape after the snapshot
+#[test]
+fn balance_updates_the_snapshot_includes_are_not_applied_again() {
+    let free = btc_free(vec![
+        position(500, "1.00000000"),
+        snapshot(1_000, "1.50000000"),
+        balance_update(1_001, 900, "0.50000000"),
+    ]);
+
+    assert_eq!(free, "1.50000000");
+}
+
+#[test]
+fn balance_updates_after_the_snapshot_are_applied() {
+    let free = btc_free(vec![snapshot(1_000, "1.50000000"), balance_update(1_200, 1_100, "0.50000000")]);
+
+    assert_eq!(free, "2.00000000");
+}
+
+#[test]
+fn unreadable_balance_updates_are_skipped() {
+    let free = btc_free(vec![
+        snapshot(1_000, "1.50000000"),
+        balance_update(1_200, 1_100, "0.5x"),
+        balance_update(1_300, 1_250, "0.25000000"),
+    ]);
+
+    assert_eq!(free, "1.75000000");
+}
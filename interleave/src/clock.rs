@@ -0,0 +1,63 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use datatypes::{Event, EventType};
+
+/// Clock offsets measured by the recorder that wrote a directory, used to move
+/// its receive times onto the exchange's clock.
+pub struct ClockCorrection {
+    // (local receive time in ms, exchange minus local offset in ms), in receive time order
+    samples: Vec<(u64, f64)>,
+}
+
+impl ClockCorrection {
+    /// Read the measurements `record` writes to `clock.json`.
+    ///
+    /// A directory without one gets no correction.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let path = dir.join("clock.json");
+        if !path.exists() {
+            return Ok(Self { samples: Vec::new() });
+        }
+
+        let mut samples = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if let Ok(Event {
+                receive_time,
+                event: EventType::ClockOffset(clock),
+                ..
+            }) = serde_json::from_str(&line)
+            {
+                samples.push((receive_time, clock.offset));
+            }
+        }
+        samples.sort_by_key(|(receive_time, _)| *receive_time);
+
+        Ok(Self { samples })
+    }
+
+    // linear between measurements, held flat before the first and after the last
+    fn offset_at(&self, receive_time: u64) -> f64 {
+        let i = self.samples.partition_point(|(t, _)| *t <= receive_time);
+
+        match (i.checked_sub(1).map(|i| self.samples[i]), self.samples.get(i).copied()) {
+            (None, None) => 0.0,
+            (Some((_, offset)), None) | (None, Some((_, offset))) => offset,
+            (Some((t0, o0)), Some((t1, o1))) => {
+                let f = (receive_time - t0) as f64 / (t1 - t0) as f64;
+                o0 + (o1 - o0) * f
+            }
+        }
+    }
+
+    pub fn apply(&self, event: &mut Event) {
+        let offset = self.offset_at(event.receive_time);
+
+        event.receive_time = (event.receive_time as f64 + offset).round() as u64;
+        event.receive_time_ns = event
+            .receive_time_ns
+            .map(|ns| (ns as f64 + offset * 1_000_000.0).round() as u64);
+    }
+}
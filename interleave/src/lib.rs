@@ -0,0 +1,127 @@
+//! Merging a recording's per-symbol files into one time ordered event stream.
+
+pub mod clock;
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use clock::ClockCorrection;
+use datatypes::Event;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OrderBy {
+    /// Local receive time
+    Receive,
+    /// Exchange event time, falling back to receive time for events without one
+    Exchange,
+}
+
+impl OrderBy {
+    pub fn key(self, event: &Event) -> u64 {
+        match self {
+            OrderBy::Receive => event.receive_time,
+            OrderBy::Exchange => event.exchange_time.unwrap_or(event.receive_time),
+        }
+    }
+}
+
+struct LineGenerator {
+    reader: BufReader<File>,
+    correction: Option<Arc<ClockCorrection>>,
+}
+
+impl LineGenerator {
+    fn new(path: PathBuf, correction: Option<Arc<ClockCorrection>>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            correction,
+        })
+    }
+
+    fn next_line(&mut self) -> Option<Event> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.trim().is_empty() {
+                    return self.next_line();
+                }
+                let mut event: Event = serde_json::from_str(&line).ok()?;
+                if let Some(correction) = &self.correction {
+                    correction.apply(&mut event);
+                }
+                Some(event)
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+fn get_files(path: &str) -> io::Result<Vec<PathBuf>> {
+    Ok(std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect())
+}
+
+/// Every event in one or more data directories, smallest [`OrderBy::key`] first.
+pub struct Interleaver {
+    generators: Vec<LineGenerator>,
+    line_data: Vec<Option<Event>>,
+    order_by: OrderBy,
+}
+
+impl Interleaver {
+    /// Open every file in `paths`, shifting each directory's receive times
+    /// onto the exchange clock first if `correct_clock` is set.
+    pub fn open(paths: &[String], order_by: OrderBy, correct_clock: bool) -> io::Result<Self> {
+        let mut generators: Vec<LineGenerator> = Vec::new();
+        for path in paths {
+            let correction = if correct_clock {
+                Some(Arc::new(ClockCorrection::load(Path::new(path))?))
+            } else {
+                None
+            };
+
+            generators.extend(
+                get_files(path)?
+                    .into_iter()
+                    .filter_map(|file| LineGenerator::new(file, correction.clone()).ok()),
+            );
+        }
+
+        let line_data = generators.iter_mut().map(|gen| gen.next_line()).collect();
+
+        Ok(Self {
+            generators,
+            line_data,
+            order_by,
+        })
+    }
+}
+
+impl Iterator for Interleaver {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let times: Vec<u64> = self
+            .line_data
+            .iter()
+            .map(|data| data.as_ref().map_or(u64::MAX, |d| self.order_by.key(d)))
+            .collect();
+
+        let smallest_time_index = times
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.line_data[*index].is_some())
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(index, _)| index)?;
+
+        let next_line = self.generators[smallest_time_index].next_line();
+        std::mem::replace(&mut self.line_data[smallest_time_index], next_line)
+    }
+}
@@ -0,0 +1,117 @@
+//! Just enough HTTP/1.1 to answer one JSON request per connection.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+
+use serde_json::Value;
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    // parameters from the query string and a form encoded body
+    pub query: HashMap<String, String>,
+    pub raw_query: String,
+}
+
+/// Status, `Retry-After` in seconds and JSON body.
+pub type Response = (u16, Option<u64>, Value);
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let (path, raw_query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    // signed requests may carry their parameters in the body
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body);
+
+    let query = raw_query
+        .split('&')
+        .chain(body.split('&'))
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    Ok(Request {
+        method,
+        path: path.to_string(),
+        query,
+        raw_query: raw_query.to_string(),
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        418 => "I'm a teapot",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+fn respond(mut stream: TcpStream, status: u16, retry_after: Option<u64>, body: &Value) -> io::Result<()> {
+    let body = body.to_string();
+
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        reason(status),
+        body.len()
+    );
+    if let Some(retry_after) = retry_after {
+        response.push_str(&format!("Retry-After: {}\r\n", retry_after));
+    }
+    response.push_str("\r\n");
+    response.push_str(&body);
+
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
+
+/// Answer every connection on `listener` with `handler`, a thread each.
+pub fn serve<F>(listener: TcpListener, handler: F)
+where
+    F: Fn(&Request) -> Response + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let handler = handler.clone();
+
+        std::thread::spawn(move || {
+            if let Ok(request) = read_request(&stream) {
+                let (status, retry_after, body) = handler(&request);
+                let _ = respond(stream, status, retry_after, &body);
+            }
+        });
+    }
+}
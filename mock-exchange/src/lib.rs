@@ -0,0 +1,117 @@
+//! A stand-in for the Binance REST and websocket APIs, serving a [`Scenario`]
+//! so the recorders can be run end to end without the real exchange.
+
+pub mod http;
+mod rest;
+pub mod scenario;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod ws;
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde_json::Value;
+
+pub use scenario::{RestReply, Scenario, Step};
+
+// handed out for every user data stream
+pub const LISTEN_KEY: &str = "mock-listen-key";
+
+/// A REST request the mock has answered.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub time: Instant,
+}
+
+// the scenario as it gets used up, plus what has been asked of us
+struct State {
+    depth: HashMap<String, VecDeque<Value>>,
+    depth_replies: VecDeque<RestReply>,
+    exchange_info: Option<Value>,
+    account: Option<Value>,
+    open_orders: Option<Value>,
+    market: VecDeque<Step>,
+    user: VecDeque<Step>,
+    requests: Vec<RecordedRequest>,
+    connections: Vec<String>,
+}
+
+impl From<Scenario> for State {
+    fn from(scenario: Scenario) -> Self {
+        Self {
+            depth: scenario
+                .depth
+                .into_iter()
+                .map(|(symbol, snapshots)| (symbol.to_uppercase(), snapshots.into()))
+                .collect(),
+            depth_replies: scenario.depth_replies.into(),
+            exchange_info: scenario.exchange_info,
+            account: scenario.account,
+            open_orders: scenario.open_orders,
+            market: scenario.market.into(),
+            user: scenario.user.into(),
+            requests: Vec::new(),
+            connections: Vec::new(),
+        }
+    }
+}
+
+/// A running mock exchange. Its server threads live as long as the process.
+///
+/// Websocket steps are shared by every connection to the same feed: after a
+/// `Disconnect` the next connection picks up from the step that follows.
+pub struct MockExchange {
+    rest_addr: SocketAddr,
+    ws_addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockExchange {
+    /// Serve the scenario on two free local ports.
+    pub fn start(scenario: Scenario) -> io::Result<Self> {
+        Self::bind(scenario, "127.0.0.1:0", "127.0.0.1:0")
+    }
+
+    pub fn bind(scenario: Scenario, rest_addr: &str, ws_addr: &str) -> io::Result<Self> {
+        let rest_listener = TcpListener::bind(rest_addr)?;
+        let ws_listener = TcpListener::bind(ws_addr)?;
+        let state = Arc::new(Mutex::new(State::from(scenario)));
+
+        let mock = Self {
+            rest_addr: rest_listener.local_addr()?,
+            ws_addr: ws_listener.local_addr()?,
+            state: state.clone(),
+        };
+
+        let rest_state = state.clone();
+        std::thread::spawn(move || http::serve(rest_listener, move |request| rest::handle(&rest_state, request)));
+        std::thread::spawn(move || ws::serve(ws_listener, state));
+
+        Ok(mock)
+    }
+
+    pub fn rest_url(&self) -> String {
+        format!("http://{}", self.rest_addr)
+    }
+
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}", self.ws_addr)
+    }
+
+    /// Every REST request answered so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The path of every websocket connection accepted so far.
+    pub fn connections(&self) -> Vec<String> {
+        self.state.lock().unwrap().connections.clone()
+    }
+}
@@ -0,0 +1,43 @@
+use clap::Parser;
+use mock_exchange::{MockExchange, Scenario};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Scenario file, or with --tape the files of a recorded tape
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Replay recorded tape files instead of a scenario
+    #[arg(long)]
+    tape: bool,
+
+    /// Address to serve REST on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    rest_addr: String,
+
+    /// Address to serve websockets on
+    #[arg(long, default_value = "127.0.0.1:8081")]
+    ws_addr: String,
+}
+
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+
+    let scenario = if args.tape {
+        Scenario::from_tape(&args.paths.iter().map(PathBuf::as_path).collect::<Vec<_>>())?
+    } else {
+        Scenario::load(&args.paths[0])?
+    };
+
+    let mock = MockExchange::bind(scenario, &args.rest_addr, &args.ws_addr)?;
+    for prefix in ["RECORD_", "ACCOUNT_"] {
+        println!("{}REST_ENDPOINT={}", prefix, mock.rest_url());
+        println!("{}WS_ENDPOINT={}", prefix, mock.ws_url());
+    }
+
+    loop {
+        std::thread::park();
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde_json::{json, Value};
+
+use crate::http::{Request, Response};
+use crate::{RecordedRequest, State};
+
+fn default_account() -> Value {
+    json!({
+        "makerCommission": 10,
+        "takerCommission": 10,
+        "buyerCommission": 0,
+        "sellerCommission": 0,
+        "canTrade": true,
+        "canWithdraw": true,
+        "canDeposit": true,
+        "balances": []
+    })
+}
+
+pub(crate) fn handle(state: &Mutex<State>, request: &Request) -> Response {
+    let mut state = state.lock().unwrap();
+    state.requests.push(RecordedRequest {
+        method: request.method.clone(),
+        path: request.path.clone(),
+        query: request.raw_query.clone(),
+        time: Instant::now(),
+    });
+
+    // spot and futures endpoints are served the same way
+    let endpoint = request
+        .path
+        .strip_prefix("/api/v3/")
+        .or_else(|| request.path.strip_prefix("/fapi/v1/"))
+        .unwrap_or_default();
+
+    match endpoint {
+        "depth" => {
+            if let Some(reply) = state.depth_replies.pop_front() {
+                return (reply.status, reply.retry_after, reply.body);
+            }
+
+            let symbol = request.query.get("symbol").cloned().unwrap_or_default();
+            match state.depth.get_mut(&symbol) {
+                Some(snapshots) if !snapshots.is_empty() => {
+                    let snapshot = if snapshots.len() > 1 {
+                        snapshots.pop_front().unwrap()
+                    } else {
+                        snapshots[0].clone()
+                    };
+                    (200, None, snapshot)
+                }
+                _ => (400, None, json!({"code": -1121, "msg": "Invalid symbol."})),
+            }
+        }
+        "time" => (200, None, json!({"serverTime": datatypes::now_ns() / 1_000_000})),
+        "exchangeInfo" => {
+            let info = state.exchange_info.clone().unwrap_or_else(|| {
+                json!({"timezone": "UTC", "serverTime": 0, "rateLimits": [], "symbols": []})
+            });
+            (200, None, info)
+        }
+        "userDataStream" => match request.method.as_str() {
+            "POST" => (200, None, json!({"listenKey": crate::LISTEN_KEY})),
+            _ => (200, None, json!({})),
+        },
+        "account" => (200, None, state.account.clone().unwrap_or_else(default_account)),
+        "openOrders" => (200, None, state.open_orders.clone().unwrap_or_else(|| json!([]))),
+        _ => (404, None, json!({"code": -1, "msg": "Not found."})),
+    }
+}
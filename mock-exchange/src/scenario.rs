@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use binance::model::{DepthOrderBookEvent, OrderBook};
+use datatypes::{Event, EventType};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+// the binance crate's models serialise levels as `{"price": .., "qty": ..}`,
+// the exchange sends `[price, qty]` string pairs
+fn levels(levels: impl Iterator<Item = (f64, f64)>) -> Value {
+    levels.map(|(price, qty)| json!([price.to_string(), qty.to_string()])).collect()
+}
+
+/// A `/api/v3/depth` reply in the exchange's wire format.
+pub fn depth_snapshot(ob: &OrderBook) -> Value {
+    json!({
+        "lastUpdateId": ob.last_update_id,
+        "bids": levels(ob.bids.iter().map(|level| (level.price, level.qty))),
+        "asks": levels(ob.asks.iter().map(|level| (level.price, level.qty))),
+    })
+}
+
+/// A `depthUpdate` in the exchange's wire format, `pu` is only sent on futures.
+pub fn depth_update(e: &DepthOrderBookEvent) -> Value {
+    let mut update = Map::new();
+    update.insert("e".to_string(), json!(e.event_type));
+    update.insert("E".to_string(), json!(e.event_time));
+    update.insert("s".to_string(), json!(e.symbol));
+    update.insert("U".to_string(), json!(e.first_update_id));
+    update.insert("u".to_string(), json!(e.final_update_id));
+    if let Some(previous) = e.previous_final_update_id {
+        update.insert("pu".to_string(), json!(previous));
+    }
+    update.insert("b".to_string(), levels(e.bids.iter().map(|level| (level.price, level.qty))));
+    update.insert("a".to_string(), levels(e.asks.iter().map(|level| (level.price, level.qty))));
+    Value::Object(update)
+}
+
+/// The combined stream name and payload a market data event arrived as, e.g.
+/// `btcusdt@aggTrade`.
+pub fn market_stream(event: &Event) -> Option<(String, Value)> {
+    let symbol = event.symbol.to_lowercase();
+    let stream = |kind: &str| format!("{}@{}", symbol, kind);
+
+    match &event.event {
+        EventType::PartialOrderBook(e) => Some((stream("depth@100ms"), depth_update(e))),
+        EventType::Trade(e) => Some((stream("aggTrade"), json!(e))),
+        EventType::BookTicker(e) => Some((stream("bookTicker"), json!(e))),
+        EventType::Kline(e) => Some((stream(&format!("kline_{}", e.kline.interval)), json!(e))),
+        EventType::MarkPrice(e) => Some((stream("markPrice@1s"), json!(e))),
+        EventType::Liquidation(e) => Some((stream("forceOrder"), json!(e))),
+        _ => None,
+    }
+}
+
+/// One thing the mock does on a websocket connection.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Step {
+    /// Send a payload, wrapped as `{"stream": .., "data": ..}` on the market stream
+    Message {
+        #[serde(default)]
+        stream: String,
+        data: Value,
+        #[serde(default)]
+        delay_ms: u64,
+    },
+    /// Drop the connection without a close frame, the next connection carries
+    /// on from the following step
+    Disconnect {
+        #[serde(default)]
+        delay_ms: u64,
+    },
+}
+
+/// A scripted REST reply, served instead of the normal response.
+#[derive(Deserialize, Debug, Clone)]
+pub struct RestReply {
+    pub status: u16,
+    #[serde(default)]
+    pub retry_after: Option<u64>,
+    #[serde(default)]
+    pub body: Value,
+}
+
+/// Everything the mock exchange serves.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Scenario {
+    // order book snapshots per symbol, served in turn with the last one repeated
+    #[serde(default)]
+    pub depth: HashMap<String, Vec<Value>>,
+    // replies to depth requests, used up before any snapshot is served
+    #[serde(default)]
+    pub depth_replies: Vec<RestReply>,
+    #[serde(default)]
+    pub exchange_info: Option<Value>,
+    #[serde(default)]
+    pub account: Option<Value>,
+    #[serde(default)]
+    pub open_orders: Option<Value>,
+    // combined market stream, `/stream?streams=..`
+    #[serde(default)]
+    pub market: Vec<Step>,
+    // user data stream, `/ws/<listen key>`
+    #[serde(default)]
+    pub user: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        serde_json::from_reader(file).map_err(io::Error::other)
+    }
+
+    /// Replay a recorded tape: stream events are sent with the spacing they
+    /// were received with, full books and account snapshots are served over REST.
+    pub fn from_tape(paths: &[&Path]) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for path in paths {
+            for line in BufReader::new(File::open(path)?).lines() {
+                let event: Event = serde_json::from_str(&line?).map_err(io::Error::other)?;
+                events.push(event);
+            }
+        }
+        events.sort_by_key(|event| event.receive_time);
+
+        let mut scenario = Scenario::default();
+        let mut last_market = None;
+        let mut last_user = None;
+
+        for event in events {
+            let market = market_stream(&event);
+            let user = match &event.event {
+                EventType::OrderTradeEvent(e) => Some(json!(e)),
+                EventType::AccountPosition(e) => Some(json!(e)),
+                EventType::BalanceUpdate(e) => Some(json!(e)),
+                _ => None,
+            };
+
+            if let Some((stream, data)) = market {
+                let delay_ms = last_market.map_or(0, |last| event.receive_time - last);
+                last_market = Some(event.receive_time);
+                scenario.market.push(Step::Message { stream, data, delay_ms });
+            } else if let Some(data) = user {
+                let delay_ms = last_user.map_or(0, |last| event.receive_time - last);
+                last_user = Some(event.receive_time);
+                scenario.user.push(Step::Message {
+                    stream: String::new(),
+                    data,
+                    delay_ms,
+                });
+            } else {
+                match event.event {
+                    EventType::FullOrderBook(ob) => {
+                        scenario.depth.entry(event.symbol.to_uppercase()).or_default().push(depth_snapshot(&ob));
+                    }
+                    EventType::AccountInformation(account) => scenario.account = Some(json!(account)),
+                    EventType::OpenOrders(orders) => scenario.open_orders = Some(json!(orders)),
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(scenario)
+    }
+}
@@ -0,0 +1,41 @@
+//! Helpers for tests that run a recorder against the mock and read back the
+//! tapes it writes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use datatypes::{Event, EventType};
+
+/// How long [`wait_for`] waits before failing the test.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A fresh, empty directory for one test's output.
+pub fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mock-exchange-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Every event in a tape, none if it hasn't been written yet.
+pub fn read_tape(path: &Path) -> Vec<Event> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+/// Poll a tape until `done` holds for it, panicking after [`TIMEOUT`].
+pub fn wait_for(path: &Path, done: impl Fn(&[Event]) -> bool) {
+    let start = Instant::now();
+    while !done(&read_tape(path)) {
+        assert!(start.elapsed() < TIMEOUT, "timed out waiting on {}", path.display());
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+pub fn count(events: &[Event], matches: fn(&EventType) -> bool) -> usize {
+    events.iter().filter(|event| matches(&event.event)).count()
+}
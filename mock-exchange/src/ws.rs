@@ -0,0 +1,85 @@
+use std::io::ErrorKind;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::json;
+use tungstenite::handshake::server::{Request, Response};
+use tungstenite::{accept_hdr, Message, WebSocket};
+
+use crate::scenario::Step;
+use crate::State;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Feed {
+    Market,
+    User,
+}
+
+fn next_step(state: &Mutex<State>, feed: Feed) -> Option<Step> {
+    let mut state = state.lock().unwrap();
+    match feed {
+        Feed::Market => state.market.pop_front(),
+        Feed::User => state.user.pop_front(),
+    }
+}
+
+// keep answering pings until the client goes away
+fn idle(socket: &mut WebSocket<TcpStream>) {
+    let _ = socket.get_ref().set_read_timeout(Some(Duration::from_millis(100)));
+    loop {
+        match socket.read() {
+            Ok(_) => (),
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => (),
+            Err(_) => return,
+        }
+    }
+}
+
+// the handshake callback's error type is tungstenite's, not ours to shrink
+#[allow(clippy::result_large_err)]
+fn run(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut path = String::new();
+    let mut socket = match accept_hdr(stream, |request: &Request, response: Response| {
+        path = request.uri().to_string();
+        Ok(response)
+    }) {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+
+    let feed = if path.starts_with("/ws/") { Feed::User } else { Feed::Market };
+    state.lock().unwrap().connections.push(path);
+
+    while let Some(step) = next_step(&state, feed) {
+        match step {
+            Step::Message { stream, data, delay_ms } => {
+                std::thread::sleep(Duration::from_millis(delay_ms));
+                let payload = match feed {
+                    Feed::Market => json!({"stream": stream, "data": data}),
+                    Feed::User => data,
+                };
+                if socket.send(Message::text(payload.to_string())).is_err() {
+                    return;
+                }
+            }
+            Step::Disconnect { delay_ms } => {
+                std::thread::sleep(Duration::from_millis(delay_ms));
+                let _ = socket.get_ref().shutdown(Shutdown::Both);
+                return;
+            }
+        }
+    }
+
+    idle(&mut socket);
+}
+
+pub(crate) fn serve(listener: TcpListener, state: Arc<Mutex<State>>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let state = state.clone();
+        std::thread::spawn(move || run(stream, state));
+    }
+}
@@ -0,0 +1,52 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use mock_exchange::{MockExchange, Scenario};
+use serde_json::Value;
+
+// a tape as `record` writes it, books in the binance crate's own shape
+const TAPE: &str = r#"{"symbol":"BTCUSDT","receive_time":1,"event":{"FullOrderBook":{"lastUpdateId":100,"bids":[{"price":"100.5","qty":"1"}],"asks":[{"price":"101","qty":"2.5"}]}}}
+{"symbol":"BTCUSDT","receive_time":2,"event":{"PartialOrderBook":{"e":"depthUpdate","E":2,"s":"BTCUSDT","U":101,"u":102,"b":[["100.5","3"]],"a":[]}}}
+"#;
+
+fn get(url: &str) -> Value {
+    let address = url.trim_start_matches("http://").split('/').next().unwrap();
+    let path = &url[url.find(address).unwrap() + address.len()..];
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", path, address).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    serde_json::from_str(body).unwrap()
+}
+
+fn pair(level: &Value) -> (&str, &str) {
+    let level = level.as_array().expect("levels are arrays");
+    assert_eq!(level.len(), 2);
+    (level[0].as_str().unwrap(), level[1].as_str().unwrap())
+}
+
+#[test]
+fn replayed_books_are_served_in_the_wire_format() {
+    let tape = std::env::temp_dir().join(format!("mock-exchange-wire-{}.json", std::process::id()));
+    fs::write(&tape, TAPE).unwrap();
+    let mock = MockExchange::start(Scenario::from_tape(&[&tape]).unwrap()).unwrap();
+    let _ = fs::remove_file(&tape);
+
+    let snapshot = get(&format!("{}/api/v3/depth?symbol=BTCUSDT", mock.rest_url()));
+    assert_eq!(snapshot["lastUpdateId"], 100);
+    assert_eq!(pair(&snapshot["bids"][0]), ("100.5", "1"));
+    assert_eq!(pair(&snapshot["asks"][0]), ("101", "2.5"));
+
+    let (mut socket, _) = tungstenite::connect(format!("{}/stream?streams=btcusdt@depth@100ms", mock.ws_url())).unwrap();
+    let message: Value = serde_json::from_str(socket.read().unwrap().to_text().unwrap()).unwrap();
+    let update = message["data"].as_object().unwrap();
+    assert_eq!(message["stream"], "btcusdt@depth@100ms");
+    assert_eq!(pair(&update["b"][0]), ("100.5", "3"));
+    assert_eq!(update["a"], Value::Array(Vec::new()));
+    // spot diffs carry no `pu`
+    assert!(!update.contains_key("pu"));
+}
@@ -0,0 +1,127 @@
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use binance::userstream::UserStream;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket, connect};
+
+// https://developers.binance.com/docs/binance-spot-api-docs/user-data-stream
+// a listen key expires 60 minutes after its last keepalive
+pub const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+// connections are dropped by the exchange after 24 hours, move to a new one first
+pub const ROTATE_AFTER: Duration = Duration::from_secs(23 * 60 * 60);
+// how long a read blocks before we get a chance to send keepalives
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+// a failed keepalive is retried well before the key runs out
+const KEEPALIVE_RETRY: Duration = Duration::from_secs(5);
+const MAX_KEEPALIVE_RETRY: Duration = Duration::from_secs(60);
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Doubling delay between reconnect attempts.
+pub struct Backoff {
+    next: Duration,
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self { next: INITIAL_BACKOFF }
+    }
+
+    pub fn next(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(MAX_BACKOFF);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.next = INITIAL_BACKOFF;
+    }
+}
+
+/// A user data stream socket and the listen key it was opened with.
+pub struct UserConnection {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    listen_key: String,
+    opened_at: Instant,
+    last_keepalive: Instant,
+    // when to try again after a failed keepalive, and how long to wait after that
+    keepalive_retry: Option<(Instant, Duration)>,
+}
+
+impl UserConnection {
+    pub fn open(user_stream: &UserStream, ws_endpoint: &str) -> Result<Self, String> {
+        let listen_key = user_stream
+            .start()
+            .map_err(|e| format!("Not able to start a user stream (check your API key): {}", e))?
+            .listen_key;
+
+        let (socket, _) = connect(format!("{}/ws/{}", ws_endpoint, listen_key)).map_err(|e| e.to_string())?;
+
+        // reads have to give up now and then so keepalives go out on a quiet account
+        let timeout = match socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(READ_TIMEOUT)),
+            MaybeTlsStream::NativeTls(stream) => stream.get_ref().set_read_timeout(Some(READ_TIMEOUT)),
+            _ => Ok(()),
+        };
+        timeout.map_err(|e| e.to_string())?;
+
+        let now = Instant::now();
+        Ok(Self {
+            socket,
+            listen_key,
+            opened_at: now,
+            last_keepalive: now,
+            keepalive_retry: None,
+        })
+    }
+
+    pub fn age(&self) -> Duration {
+        self.opened_at.elapsed()
+    }
+
+    /// Extend the listen key if a keepalive is due, failures are retried with
+    /// a backoff until one goes through.
+    pub fn keep_alive(&mut self, user_stream: &UserStream) -> Result<(), String> {
+        if self.last_keepalive.elapsed() < KEEPALIVE_INTERVAL {
+            return Ok(());
+        }
+        if let Some((retry_at, _)) = self.keepalive_retry
+            && Instant::now() < retry_at
+        {
+            return Ok(());
+        }
+
+        match user_stream.keep_alive(&self.listen_key) {
+            Ok(_) => {
+                self.last_keepalive = Instant::now();
+                self.keepalive_retry = None;
+                Ok(())
+            }
+            Err(e) => {
+                let delay = self.keepalive_retry.map_or(KEEPALIVE_RETRY, |(_, delay)| delay);
+                self.keepalive_retry = Some((Instant::now() + delay, (delay * 2).min(MAX_KEEPALIVE_RETRY)));
+                Err(format!("Listen key keepalive failed, retrying in {}s: {}", delay.as_secs(), e))
+            }
+        }
+    }
+
+    /// The next message, or `None` if nothing arrived within the read timeout.
+    // tungstenite's error is large, keep it off the stack
+    pub fn read(&mut self) -> Result<Option<Message>, Box<tungstenite::Error>> {
+        match self.socket.read() {
+            Ok(message) => Ok(Some(message)),
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                Ok(None)
+            }
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    pub fn close(mut self) {
+        let _ = self.socket.close(None);
+    }
+}
@@ -1,25 +1,52 @@
+mod connection;
+
+use binance::account::Account;
 use binance::config::Config;
-use binance::model::{BalanceUpdateEvent, OrderTradeEvent};
 use binance::userstream::*;
-use binance::{api::*, model::AccountUpdateEvent};
+use binance::api::*;
+use connection::{Backoff, ROTATE_AFTER, UserConnection};
+use datatypes::endpoints::{Endpoints, Network};
+use datatypes::{AccountPosition, BalanceUpdate, EventType, ExecutionReport, StreamGap};
+use clap::Parser;
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::io::Write;
-use std::sync::atomic::AtomicBool;
-use tungstenite::{Message, connect};
-use url::Url;
+use std::time::{Duration, Instant};
+use tungstenite::Message;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directory to write account.json to
+    output_dir: String,
+
+    /// Seconds between account information and open order snapshots
+    #[arg(long, default_value_t = 300)]
+    snapshot_interval: u64,
+
+    /// mainnet or testnet
+    #[arg(long, env = "BINANCE_NETWORK", default_value_t = Network::Mainnet)]
+    network: Network,
+
+    /// REST base URL, overrides the network's (or ACCOUNT_REST_ENDPOINT)
+    #[arg(long)]
+    rest_endpoint: Option<String>,
+
+    /// Websocket base URL, overrides the network's (or ACCOUNT_WS_ENDPOINT)
+    #[arg(long)]
+    ws_endpoint: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 enum Events {
-    BalanceUpdateEvent(BalanceUpdateEvent),
-    AccountUpdateEvent(AccountUpdateEvent),
-    OrderTradeEvent(OrderTradeEvent),
+    AccountPosition(AccountPosition),
+    BalanceUpdate(BalanceUpdate),
+    OrderTradeEvent(ExecutionReport),
 }
 
-fn write_trade_event(output_dir: &str, event: &OrderTradeEvent) -> Result<(), std::io::Error> {
+fn write_account_event(output_dir: &str, recv_time: u64, event: EventType) -> Result<(), std::io::Error> {
     let file_name = format!("{}/account.json", output_dir);
-    let recv_time = chrono::Utc::now().timestamp_millis() as u64;
 
     let mut file = std::fs::OpenOptions::new()
         .create(true)
@@ -28,7 +55,7 @@ fn write_trade_event(output_dir: &str, event: &OrderTradeEvent) -> Result<(), st
         .open(&file_name)
         .unwrap();
 
-    let event = datatypes::Event::new("account".to_string(), recv_time, datatypes::EventType::OrderTradeEvent(event.clone()));
+    let event = datatypes::Event::received("account".to_string(), recv_time, event);
 
     let event = serde_json::to_string(&event).unwrap();
     let to_write = format!("{}\n", event);
@@ -36,56 +63,149 @@ fn write_trade_event(output_dir: &str, event: &OrderTradeEvent) -> Result<(), st
     file.write_all(to_write.as_bytes())
 }
 
+fn now_ms() -> u64 {
+    datatypes::now_ns() / 1_000_000
+}
+
+// mark a stretch of time in which account events may have been missed
+fn write_gap(output_dir: &str, from: u64, to: u64, reason: &str) {
+    let gap = StreamGap {
+        from,
+        to,
+        reason: reason.to_string(),
+    };
+    write_account_event(output_dir, datatypes::now_ns(), EventType::StreamGap(gap)).unwrap();
+}
+
+// balances and open orders from REST, so the world doesn't rely on having seen every update
+fn write_snapshot(output_dir: &str, account: &Account) {
+    let recv_time = datatypes::now_ns();
+    match account.get_account() {
+        Ok(info) => write_account_event(output_dir, recv_time, EventType::AccountInformation(info)).unwrap(),
+        Err(e) => println!("Account information snapshot failed: {}", e),
+    }
+
+    let recv_time = datatypes::now_ns();
+    match account.get_all_open_orders() {
+        Ok(orders) => write_account_event(output_dir, recv_time, EventType::OpenOrders(orders)).unwrap(),
+        Err(e) => println!("Open orders snapshot failed: {}", e),
+    }
+}
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let output_dir = args[1].clone();
+    let args = Args::parse();
+    let output_dir = args.output_dir.clone();
+    let snapshot_interval = Duration::from_secs(args.snapshot_interval);
 
     let api_key_user = env::var("BINANCE_API_KEY").ok();
     let api_key_user_secret = env::var("BINANCE_API_SECRET_KEY").ok();
-    let rest_api_endpoint = env::var("REST_ENDPOINT").unwrap();
+    let endpoints = Endpoints::spot(args.network)
+        .overridden_by_env("ACCOUNT_")
+        .overridden_by(args.rest_endpoint, args.ws_endpoint);
 
-    let config = Config::default().set_rest_api_endpoint(rest_api_endpoint);
+    let config = Config::default().set_rest_api_endpoint(endpoints.rest.clone());
 
     let user_stream: UserStream =
-        Binance::new_with_config(api_key_user, api_key_user_secret, &config);
+        Binance::new_with_config(api_key_user.clone(), api_key_user_secret.clone(), &config);
+    let account: Account = Binance::new_with_config(api_key_user, api_key_user_secret, &config);
 
-    loop {
-        if let Ok(answer) = user_stream.start() {
-            let listen_key = answer.listen_key;
+    let mut backoff = Backoff::new();
+    // when the stream went down, None while connected or before the first
+    // connection: failing to connect at startup leaves no gap in the tape
+    let mut down_since: Option<u64> = None;
 
-            let endpoint = "wss://stream.testnet.binance.vision:9443/ws/".to_string() + &listen_key;
+    loop {
+        let mut connection = match UserConnection::open(&user_stream, &endpoints.ws) {
+            Ok(connection) => connection,
+            Err(e) => {
+                // after a disconnect `down_since` is already set, the failed
+                // attempts only stretch the gap
+                println!("{}", e);
+
+                let delay = backoff.next();
+                println!("Reconnecting in {} seconds...", delay.as_secs());
+                std::thread::sleep(delay);
+                continue;
+            }
+        };
+        backoff.reset();
 
-            let (mut socket, _) = connect(&endpoint).expect("Can't connect");
+        if let Some(from) = down_since.take() {
+            write_gap(&output_dir, from, now_ms(), "disconnected");
+        }
 
-            // Handle WebSocket messages
-            while let Ok(message) = socket.read() {
-                let text = match message {
-                    Message::Text(text) => text,
-                    _ => continue,
-                };
-                let value: serde_json::Value = serde_json::from_str(&text).unwrap();
-
-                let event = serde_json::from_value::<Events>(value);
-
-                if let Ok(event) = event {
-                    match event {
-                        Events::BalanceUpdateEvent(e) => {
-                            panic!("AccountUpdateEvent: {:?}", e);
-                        }
-                        Events::AccountUpdateEvent(e) => {
-                            panic!("AccountUpdateEvent: {:?}", e);
-                        }
-                        Events::OrderTradeEvent(e) => {
-                            write_trade_event(&output_dir, &e).unwrap();
-                        }
+        // taken once the stream is up so no update falls between the snapshot and the stream
+        write_snapshot(&output_dir, &account);
+        let mut last_snapshot = Instant::now();
+
+        let mut rotate_after = ROTATE_AFTER;
+
+        loop {
+            if connection.age() >= rotate_after {
+                // open the new connection before dropping the old one, only
+                // events sent while it connects can be missed
+                let from = now_ms();
+                match UserConnection::open(&user_stream, &endpoints.ws) {
+                    Ok(new_connection) => {
+                        std::mem::replace(&mut connection, new_connection).close();
+                        rotate_after = ROTATE_AFTER;
+                        write_gap(&output_dir, from, now_ms(), "rotated");
+                        write_snapshot(&output_dir, &account);
+                        last_snapshot = Instant::now();
                     }
+                    Err(e) => {
+                        println!("Listen key rotation failed, retrying later: {}", e);
+                        rotate_after = connection.age() + backoff.next();
+                    }
+                }
+            }
+
+            if let Err(e) = connection.keep_alive(&user_stream) {
+                println!("{}", e);
+            }
+
+            if last_snapshot.elapsed() >= snapshot_interval {
+                write_snapshot(&output_dir, &account);
+                last_snapshot = Instant::now();
+            }
+
+            let message = match connection.read() {
+                Ok(Some(message)) => message,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("Error: {:?}", e);
+                    down_since = Some(now_ms());
+                    break;
+                }
+            };
+
+            let recv_time = datatypes::now_ns();
+            let text = match message {
+                Message::Text(text) => text,
+                _ => continue,
+            };
+            let value: serde_json::Value = match serde_json::from_str(&text) {
+                Ok(value) => value,
+                Err(e) => {
+                    println!("Skipping a message that isn't JSON ({}): {}", e, text);
+                    continue;
                 }
+            };
+
+            let event = serde_json::from_value::<Events>(value);
+
+            if let Ok(event) = event {
+                let event = match event {
+                    Events::AccountPosition(e) => EventType::AccountPosition(e),
+                    Events::BalanceUpdate(e) => EventType::BalanceUpdate(e),
+                    Events::OrderTradeEvent(e) => EventType::OrderTradeEvent(e),
+                };
+                write_account_event(&output_dir, recv_time, event).unwrap();
             }
-        } else {
-            println!("Not able to start an User Stream (Check your API_KEY)");
         }
 
-        // sleep for 5 seconds to avoid getting kicked off
-        std::thread::sleep(std::time::Duration::from_secs(5));
+        let delay = backoff.next();
+        println!("Reconnecting in {} seconds...", delay.as_secs());
+        std::thread::sleep(delay);
     }
 }
@@ -0,0 +1,70 @@
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+
+use datatypes::EventType;
+use mock_exchange::testing::{count, output_dir, read_tape, wait_for};
+use mock_exchange::{MockExchange, Scenario};
+use serde_json::json;
+
+fn start_record_account(mock: &MockExchange, dir: &Path) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_record-account"))
+        .arg(dir)
+        .args(["--rest-endpoint", &mock.rest_url()])
+        .args(["--ws-endpoint", &mock.ws_url()])
+        .env("BINANCE_API_KEY", "key")
+        .env("BINANCE_API_SECRET_KEY", "secret")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap()
+}
+
+#[test]
+fn records_user_stream_and_snapshots_across_disconnects() {
+    let scenario: Scenario = serde_json::from_value(json!({
+        "account": {
+            "makerCommission": 10, "takerCommission": 10, "buyerCommission": 0, "sellerCommission": 0,
+            "canTrade": true, "canWithdraw": true, "canDeposit": true,
+            "balances": [{"asset": "BTC", "free": "1.00000000", "locked": "0.00000000"}]
+        },
+        "user": [
+            {
+                "type": "message",
+                "data": {"e": "balanceUpdate", "E": 1_700_000_000_000u64, "a": "BTC", "d": "0.50000000", "T": 1_700_000_000_000u64}
+            },
+            {"type": "disconnect", "delay_ms": 100},
+            {
+                "type": "message",
+                "data": {"e": "outboundAccountPosition", "E": 1_700_000_001_000u64, "u": 1_700_000_001_000u64, "B": [{"a": "BTC", "f": "2.00000000", "l": "0.00000000"}]}
+            }
+        ]
+    }))
+    .unwrap();
+    let mock = MockExchange::start(scenario).unwrap();
+
+    let dir = output_dir("user-stream");
+    let mut record_account = start_record_account(&mock, &dir);
+    let tape = dir.join("account.json");
+    wait_for(&tape, |events| count(events, |e| matches!(e, EventType::AccountPosition(_))) == 1);
+    record_account.kill().unwrap();
+    record_account.wait().unwrap();
+
+    let events = read_tape(&tape);
+    assert_eq!(count(&events, |e| matches!(e, EventType::BalanceUpdate(_))), 1);
+    // a snapshot on the first connection and again on the reconnect
+    assert_eq!(count(&events, |e| matches!(e, EventType::AccountInformation(_))), 2);
+    assert_eq!(count(&events, |e| matches!(e, EventType::OpenOrders(_))), 2);
+
+    let gaps = events
+        .iter()
+        .filter_map(|e| match &e.event {
+            EventType::StreamGap(gap) => Some(gap),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(gaps.len(), 1);
+    assert_eq!(gaps[0].reason, "disconnected");
+    assert!(gaps[0].from <= gaps[0].to);
+
+    assert_eq!(mock.connections(), vec![format!("/ws/{}", mock_exchange::LISTEN_KEY); 2]);
+}
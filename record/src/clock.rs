@@ -0,0 +1,33 @@
+use datatypes::ClockOffset;
+
+use crate::rest::{RestClient, RestError};
+
+/// Measure the offset of the local clock from the exchange's.
+///
+/// Takes `samples` round trips to the server time endpoint and keeps the one
+/// with the shortest round trip, since it bounds the error on the offset the
+/// tightest. Returns the measurement with the local time it completed at.
+pub fn measure_offset(rest: &RestClient, samples: usize) -> Result<(u64, ClockOffset), RestError> {
+    let mut best: Option<(u64, ClockOffset)> = None;
+
+    for _ in 0..samples {
+        let sent = datatypes::now_ns();
+        let server_time = rest.get_server_time()?.server_time;
+        let received = datatypes::now_ns();
+
+        let round_trip = (received - sent) as f64 / 1_000_000.0;
+        let midpoint = (sent + received) as f64 / 2.0 / 1_000_000.0;
+
+        let sample = ClockOffset {
+            offset: server_time as f64 - midpoint,
+            round_trip,
+            server_time,
+        };
+
+        if best.is_none_or(|(_, b)| sample.round_trip < b.round_trip) {
+            best = Some((received, sample));
+        }
+    }
+
+    Ok(best.expect("at least one sample"))
+}
@@ -0,0 +1,59 @@
+use datatypes::symbol_info::decimals;
+use datatypes::SymbolInfo;
+use serde::Deserialize;
+
+// Only what we need out of the exchange info. The binance crate's model can't
+// read futures exchange info and fails on filter types it doesn't know about.
+#[derive(Deserialize)]
+pub struct ExchangeInfo {
+    pub symbols: Vec<ExchangeSymbol>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeSymbol {
+    pub symbol: String,
+    pub status: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    filters: Vec<serde_json::Value>,
+}
+
+impl ExchangeSymbol {
+    fn filter(&self, filter_type: &str) -> Option<&serde_json::Value> {
+        self.filters.iter().find(|filter| filter["filterType"] == filter_type)
+    }
+
+    /// Pull the price, lot size and notional rules out of the symbol's filters.
+    pub fn symbol_info(&self) -> Option<SymbolInfo> {
+        let field = |filter: &serde_json::Value, name: &str| filter[name].as_str().map(str::to_string);
+
+        let price_filter = self.filter("PRICE_FILTER")?;
+        let tick_size = field(price_filter, "tickSize")?;
+        let lot_size = self.filter("LOT_SIZE")?;
+        let step_size = field(lot_size, "stepSize")?;
+
+        // spot has NOTIONAL (or the older MIN_NOTIONAL) with minNotional, futures
+        // has MIN_NOTIONAL with notional
+        let min_notional = ["NOTIONAL", "MIN_NOTIONAL"]
+            .iter()
+            .filter_map(|filter_type| self.filter(filter_type))
+            .find_map(|filter| field(filter, "minNotional").or_else(|| field(filter, "notional")))
+            .and_then(|value| value.parse().ok());
+
+        Some(SymbolInfo {
+            status: self.status.clone(),
+            base_asset: self.base_asset.clone(),
+            quote_asset: self.quote_asset.clone(),
+            tick_size: tick_size.parse().ok()?,
+            min_price: field(price_filter, "minPrice")?.parse().ok()?,
+            max_price: field(price_filter, "maxPrice")?.parse().ok()?,
+            step_size: step_size.parse().ok()?,
+            min_qty: field(lot_size, "minQty")?.parse().ok()?,
+            max_qty: field(lot_size, "maxQty")?.parse().ok()?,
+            min_notional,
+            price_decimals: decimals(&tick_size),
+            qty_decimals: decimals(&step_size),
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use binance::model::MarkPriceEvent;
+use datatypes::FundingRate;
+
+/// Watches mark price updates for funding times going by.
+///
+/// Each mark price update carries the funding rate that will be applied at the
+/// next funding time, so the rate in the last update before `next_funding_time`
+/// moves on is the rate that was actually charged.
+#[derive(Default)]
+pub struct FundingTracker {
+    last_mark: HashMap<String, MarkPriceEvent>,
+}
+
+impl FundingTracker {
+    pub fn update(&mut self, symbol: &str, mark: &MarkPriceEvent) -> Option<FundingRate> {
+        let previous = self.last_mark.insert(symbol.to_string(), mark.clone())?;
+
+        if mark.next_funding_time <= previous.next_funding_time {
+            return None;
+        }
+
+        Some(FundingRate {
+            funding_rate: previous.funding_rate.parse().ok()?,
+            funding_time: previous.next_funding_time,
+            mark_price: previous.mark_price.parse().ok()?,
+        })
+    }
+}
@@ -1,36 +1,95 @@
+mod clock;
+mod exchange_info;
+mod funding;
+mod rate_limit;
+mod rest;
 mod spinner;
+mod stream;
+mod writer;
 
-use binance::account::Account;
-use binance::config::Config;
-use binance::futures::model::AccountInformation;
-use binance::futures::model::BookTickers::AllBookTickers;
-use binance::userstream::UserStream;
-use binance::{api::Binance, market::Market, websockets::*};
-
+use clap::Parser;
 use crossfire::mpsc;
 use human_repr::HumanCount;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use std::{env, vec};
-use std::{io::Write, sync::atomic::AtomicBool};
+use tokio::signal::unix::{signal, SignalKind};
 use tokio::task;
 
 use indicatif::ProgressBar;
+use funding::FundingTracker;
+use rate_limit::{RateLimitState, RateLimiter};
+use rest::{Market, RestClient};
 use spinner::*;
+use stream::MarketStream;
+use writer::{TapeWriter, WriterConfig};
 
 const CORRECTION_INTERVAL: i64 = 50;
 const N_SYMBOLS: usize = 100;
 const CORRECTION_TIMEOUT: u64 = 500;
+const CORRECTION_DEPTH: u64 = 500;
+const CLOCK_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+const CLOCK_SYNC_SAMPLES: usize = 5;
+const EXCHANGE_INFO_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const STATS_INTERVAL: Duration = Duration::from_secs(10);
+
+const WRITER_CONFIG: WriterConfig = WriterConfig {
+    reorder_window: Duration::from_millis(1_000),
+    flush_interval: Duration::from_millis(100),
+    fsync_interval: Duration::from_millis(5_000),
+};
+
+use datatypes::endpoints::{Endpoints, Network};
+use datatypes::recorder_stats::LatencyHistogram;
+use datatypes::{Event, EventType, RecorderStats};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directory to write the per-symbol files to
+    output_dir: String,
+
+    /// Also record each symbol's best bid/offer ticker
+    #[arg(long)]
+    book_ticker: bool,
+
+    /// Also record the exchange's candles at this interval (e.g. 1m, 1h)
+    #[arg(long, value_name = "INTERVAL")]
+    klines: Option<String>,
+
+    /// Record USDⓈ-M perpetual futures instead of spot
+    #[arg(long)]
+    futures: bool,
+
+    /// mainnet or testnet
+    #[arg(long, env = "BINANCE_NETWORK", default_value_t = Network::Mainnet)]
+    network: Network,
+
+    /// REST base URL, overrides the network's (or RECORD_REST_ENDPOINT / FUTURES_REST_ENDPOINT)
+    #[arg(long)]
+    rest_endpoint: Option<String>,
+
+    /// Websocket base URL, overrides the network's (or RECORD_WS_ENDPOINT / FUTURES_WS_ENDPOINT)
+    #[arg(long)]
+    ws_endpoint: Option<String>,
+}
 
-use datatypes::{Event, EventType};
-
-#[derive(Clone, Copy)]
 struct RunTimeStats {
     n_data_points: usize,
     bytes_written: usize,
     n_full_books: usize,
+    n_trades: usize,
+    n_gaps: usize,
+    n_reconnects: usize,
     start_time: u64,
+    rate_limit: RateLimitState,
+    // since the last RecorderStats event
+    interval_start: u64,
+    messages: BTreeMap<String, u64>,
+    full_books: BTreeMap<String, u64>,
+    latency: LatencyHistogram,
 }
 
 impl RunTimeStats {
@@ -39,7 +98,37 @@ impl RunTimeStats {
             n_data_points: 1,
             bytes_written: 0,
             n_full_books: 1,
+            n_trades: 0,
+            n_gaps: 0,
+            n_reconnects: 0,
             start_time: chrono::Utc::now().timestamp_millis() as u64,
+            rate_limit: RateLimitState::default(),
+            interval_start: chrono::Utc::now().timestamp_millis() as u64,
+            messages: BTreeMap::new(),
+            full_books: BTreeMap::new(),
+            latency: LatencyHistogram::default(),
+        }
+    }
+
+    // the stats to publish, starting a new interval
+    fn take_interval(&mut self) -> RecorderStats {
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        let interval_ms = now - std::mem::replace(&mut self.interval_start, now);
+
+        RecorderStats {
+            start_time: self.start_time,
+            interval_ms,
+            messages: std::mem::take(&mut self.messages),
+            full_books: std::mem::take(&mut self.full_books),
+            latency: std::mem::take(&mut self.latency),
+            bytes_written: self.bytes_written as u64,
+            n_gaps: self.n_gaps as u64,
+            n_reconnects: self.n_reconnects as u64,
+            n_rest_errors: self.rate_limit.n_rest_errors as u64,
+            n_rate_limited: self.rate_limit.n_rate_limited as u64,
+            n_banned: self.rate_limit.n_banned as u64,
+            used_weight: self.rate_limit.used_weight,
+            weight_limit: self.rate_limit.weight_limit,
         }
     }
 
@@ -56,15 +145,37 @@ impl Display for RunTimeStats {
             (N_SYMBOLS as f64 * (self.elapsed_time() as f64 / 60_000.0)) / self.n_full_books as f64;
         write!(
             f,
-            "{}",
-            format!(
-                "[Symbols:   {}] [Symbolm/fb {:.5}] [Samples: {:>7}] [Written: {:>8}]",
-                N_SYMBOLS,
-                fb_per_symbol_per_minute.to_string(),
-                self.n_data_points.human_count_bare().to_string(),
-                self.bytes_written.human_count_bytes().to_string()
-            )
-        )
+            "[Symbols:   {}] [Symbolm/fb {:.5}] [Samples: {:>7}] [Trades: {:>7}] [Gaps: {}] [Written: {:>8}] [Weight: {:>4}/{}] [429s: {}] [418s: {}] [REST errors: {}]",
+            N_SYMBOLS,
+            fb_per_symbol_per_minute.to_string(),
+            self.n_data_points.human_count_bare().to_string(),
+            self.n_trades.human_count_bare().to_string(),
+            self.n_gaps,
+            self.bytes_written.human_count_bytes().to_string(),
+            self.rate_limit.used_weight,
+            self.rate_limit.weight_limit,
+            self.rate_limit.n_rate_limited,
+            self.rate_limit.n_banned,
+            self.rate_limit.n_rest_errors
+        )?;
+
+        if !self.rate_limit.backoff_remaining.is_zero() {
+            write!(f, " [Backing off: {}s]", self.rate_limit.backoff_remaining.as_secs())?;
+        }
+
+        Ok(())
+    }
+}
+
+// a tape that can't be written ends the recording, the writer thread has
+// already said why
+fn write_or_stop(writer: &TapeWriter, event: &Event, keep_running: &AtomicBool) -> Option<usize> {
+    match writer.write(event) {
+        Ok(bytes_written) => Some(bytes_written),
+        Err(_) => {
+            keep_running.store(false, Ordering::Relaxed);
+            None
+        }
     }
 }
 
@@ -74,16 +185,26 @@ async fn main() {
     pb.enable_steady_tick(Duration::from_millis(1_000));
     pb.set_style(spinner());
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: {} <output-dir>", args[0]);
-        std::process::exit(1);
-    }
-
-    // open lines in symbols file as vec
-    let market: Market = Binance::new(None, None);
-
-    let symbols = vec!["bnbeth", "ethbtc", "btcusdt", "ethusdt", "bnbusdt"];
+    let args = Args::parse();
+
+    let (market, endpoints) = if args.futures {
+        (Market::Futures, Endpoints::futures(args.network).overridden_by_env("FUTURES_"))
+    } else {
+        (Market::Spot, Endpoints::spot(args.network).overridden_by_env("RECORD_"))
+    };
+    let endpoints = endpoints.overridden_by(args.rest_endpoint.clone(), args.ws_endpoint.clone());
+
+    // shared between every REST caller so we never exceed the weight limit
+    let rate_limiter = Arc::new(Mutex::new(RateLimiter::new(market.weight_limit())));
+    // reqwest's blocking client must not be built or driven on an async worker
+    let rest = Arc::new(task::block_in_place(|| {
+        RestClient::new(endpoints.rest.clone(), market, rate_limiter.clone())
+    }));
+
+    let symbols = match market {
+        Market::Spot => ["bnbeth", "ethbtc", "btcusdt", "ethusdt", "bnbusdt"],
+        Market::Futures => ["btcusdt", "ethusdt", "bnbusdt", "solusdt", "xrpusdt"],
+    };
     let symbols = symbols
         .iter()
         .map(|s| s.to_string())
@@ -91,59 +212,149 @@ async fn main() {
 
     let symbols = symbols.iter().take(N_SYMBOLS).collect::<Vec<&String>>();
 
-    let keep_running = AtomicBool::new(true); // Used to control the event loop
-    let depth = symbols
+    let keep_running = Arc::new(AtomicBool::new(true)); // Used to control the event loop
+    let mut streams = symbols
         .iter()
-        .map(|symbol| format!("{}@depth@100ms", symbol))
+        .flat_map(|symbol| [format!("{}@depth@100ms", symbol), format!("{}@aggTrade", symbol)])
         .collect::<Vec<String>>();
 
+    if args.book_ticker {
+        streams.extend(symbols.iter().map(|symbol| format!("{}@bookTicker", symbol)));
+    }
+
+    if let Some(interval) = &args.klines {
+        streams.extend(symbols.iter().map(|symbol| format!("{}@kline_{}", symbol, interval)));
+    }
+
+    if market == Market::Futures {
+        streams.extend(
+            symbols
+                .iter()
+                .flat_map(|symbol| [format!("{}@markPrice@1s", symbol), format!("{}@forceOrder", symbol)]),
+        );
+    }
+
     let mut ticks_since_last_correction = vec![0; N_SYMBOLS];
+    // final update id of the last diff seen per symbol, to spot dropped diffs
+    let mut last_update_ids: HashMap<String, u64> = HashMap::new();
+    let mut funding = FundingTracker::default();
 
     let (tx, rx) = mpsc::bounded_tx_blocking_rx_future::<String>(N_SYMBOLS);
 
     let runtime_stats = Arc::new(Mutex::new(RunTimeStats::new()));
     let bg_runtime_stats = runtime_stats.clone();
 
-    let output_dir = args[1].clone();
+    let output_dir = args.output_dir.clone();
+    let (writer, writer_thread) = TapeWriter::spawn(output_dir, WRITER_CONFIG);
 
-    // Spawn a background task
-    let _ = task::spawn(async move {
-        let args = env::args().collect::<Vec<String>>();
-        let output_dir = args[1].clone();
+    // on SIGINT/SIGTERM stop reading, the writer is drained once the socket loop exits
+    let shutdown_keep_running = keep_running.clone();
+    task::spawn(async move {
+        let mut sigterm = signal(SignalKind::terminate()).unwrap();
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = sigterm.recv() => {}
+        }
+        shutdown_keep_running.store(false, Ordering::Relaxed);
+    });
 
-        while let Ok(symbol) = rx.recv().await {
-            // sleep to avoid binance kicking us off
-            tokio::time::sleep(Duration::from_millis(CORRECTION_TIMEOUT)).await;
+    // measure our clock against the exchange's so latency can be corrected later
+    let clock_rest = rest.clone();
+    let clock_writer = writer.clone();
+    let clock_keep_running = keep_running.clone();
+    let clock_pb = pb.clone();
+    task::spawn(async move {
+        loop {
+            match task::block_in_place(|| clock::measure_offset(&clock_rest, CLOCK_SYNC_SAMPLES)) {
+                Ok((recv_time, offset)) => {
+                    let event = Event::received("clock".to_string(), recv_time, EventType::ClockOffset(offset));
+                    if write_or_stop(&clock_writer, &event, &clock_keep_running).is_none() {
+                        return;
+                    }
+                }
+                Err(e) => clock_pb.println(format!("Clock sync failed: {}", e)),
+            }
+            tokio::time::sleep(CLOCK_SYNC_INTERVAL).await;
+        }
+    });
 
-            let recv_time = chrono::Utc::now().timestamp_millis() as u64;
+    // trading rules per symbol, so the tape says which were in effect at any time
+    let info_rest = rest.clone();
+    let info_writer = writer.clone();
+    let info_keep_running = keep_running.clone();
+    let info_pb = pb.clone();
+    let info_symbols = symbols.iter().map(|s| s.to_uppercase()).collect::<Vec<String>>();
+    task::spawn(async move {
+        loop {
+            let recv_time = datatypes::now_ns();
+            match task::block_in_place(|| info_rest.get_exchange_info()) {
+                Ok(info) => {
+                    for symbol in info.symbols.iter().filter(|s| info_symbols.contains(&s.symbol)) {
+                        match symbol.symbol_info() {
+                            Some(symbol_info) => {
+                                let event =
+                                    Event::received(symbol.symbol.clone(), recv_time, EventType::SymbolInfo(symbol_info));
+                                if write_or_stop(&info_writer, &event, &info_keep_running).is_none() {
+                                    return;
+                                }
+                            }
+                            None => info_pb.println(format!("No price or lot size filter for {}", symbol.symbol)),
+                        }
+                    }
+                }
+                Err(e) => info_pb.println(format!("Exchange info fetch failed: {}", e)),
+            }
+            tokio::time::sleep(EXCHANGE_INFO_INTERVAL).await;
+        }
+    });
 
-            let file_name = format!("{}/{}.json", output_dir, symbol);
+    // so `watch` can show how the recorder is doing from the tape alone
+    let stats_writer = writer.clone();
+    let stats_keep_running = keep_running.clone();
+    let stats_runtime_stats = runtime_stats.clone();
+    task::spawn(async move {
+        loop {
+            tokio::time::sleep(STATS_INTERVAL).await;
+            let stats = stats_runtime_stats.lock().unwrap().take_interval();
+            let event = Event::received("record".to_string(), datatypes::now_ns(), EventType::RecorderStats(stats));
+            if write_or_stop(&stats_writer, &event, &stats_keep_running).is_none() {
+                return;
+            }
+        }
+    });
 
-            // create file if not exists
-            let mut file = std::fs::OpenOptions::new()
-                .create(true)
-                .write(true)
-                .append(true)
-                .open(&file_name)
-                .unwrap();
+    let bg_pb = pb.clone();
+    let bg_writer = writer.clone();
+    let bg_keep_running = keep_running.clone();
+
+    // Spawn a background task
+    task::spawn(async move {
+        while let Ok(symbol) = rx.recv().await {
+            // sleep to avoid binance kicking us off
+            tokio::time::sleep(Duration::from_millis(CORRECTION_TIMEOUT)).await;
 
             // send order correction
-            let answer = match market.get_custom_depth(&symbol, 500) {
+            let result = task::block_in_place(|| rest.get_depth(&symbol, CORRECTION_DEPTH));
+            // stamped once it's back, the rate limiter can hold the request for up to a minute
+            let recv_time = datatypes::now_ns();
+            bg_runtime_stats.lock().unwrap().rate_limit = rate_limiter.lock().unwrap().state();
+
+            let answer = match result {
                 Ok(answer) => answer,
-                Err(_) => {
-                    //println!("Error: {:?}", symbol);
+                Err(e) => {
+                    bg_pb.println(format!("Correction for {} failed: {}", symbol, e));
                     continue;
                 }
             };
 
-            let answer = Event::new(symbol.clone(), recv_time, EventType::FullOrderBook(answer));
+            let answer = Event::received(symbol.clone(), recv_time, EventType::FullOrderBook(answer));
 
-            // serialise to json
-            let depth_order_book = serde_json::to_string(&answer).unwrap();
-
-            // write to file
-            let to_write = format!("{}\n", depth_order_book);
-            file.write_all(to_write.as_bytes()).unwrap();
+            // the snapshot goes on the tape at the time it arrived, after the diffs received
+            // while the request was throttled or in flight; readers drop diffs it already
+            // includes by update id, see `PartialTransformer`
+            let Some(bytes_written) = write_or_stop(&bg_writer, &answer, &bg_keep_running) else {
+                return;
+            };
 
             // increment data points counter
             // increment runtime stats
@@ -151,90 +362,138 @@ async fn main() {
                 let mut stats = bg_runtime_stats.lock().unwrap();
                 stats.n_data_points += 1;
                 stats.n_full_books += 1;
-                stats.bytes_written += depth_order_book.as_bytes().len();
+                stats.bytes_written += bytes_written;
+                *stats.full_books.entry(symbol).or_default() += 1;
             }
         }
     });
 
-    loop {
-        let mut web_socket = WebSockets::new(|event: WebsocketEvent| {
-            //println!("Event n: {:?}", n_data_points);
+    while keep_running.load(Ordering::Relaxed) {
+        let mut stream = match MarketStream::connect(&endpoints.ws, &streams) {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("Error: {:?}", e);
+                println!("Reconnecting in 5 seconds...");
+                runtime_stats.lock().unwrap().n_reconnects += 1;
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+
+        while keep_running.load(Ordering::Relaxed) {
+            let message = match stream.next() {
+                Ok(Some(message)) => message,
+                // quiet stream, go round to check we should still be running
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("Error: {:?}", e);
+                    break;
+                }
+            };
+
             {
                 let runtime_stats = runtime_stats.lock().unwrap();
                 pb.set_message(runtime_stats.to_string());
             }
 
-            match event {
-                // 24hr rolling window ticker statistics for all symbols that changed in an array.
-                WebsocketEvent::DepthOrderBook(depth_order_book) => {
-                    let recv_time = chrono::Utc::now().timestamp_millis() as u64;
-                    let symbol = depth_order_book.symbol.clone();
-
-                    let depth_order_book = Event::new(
-                        depth_order_book.symbol.clone(),
-                        recv_time,
-                        EventType::PartialOrderBook(depth_order_book),
-                    );
-
-                    // append under {output_dir}/{symbol} directory
-                    let file_name = format!("{}/{}.json", output_dir, symbol);
-
-                    // create file if not exists
-                    let mut file = std::fs::OpenOptions::new()
-                        .create(true)
-                        .write(true)
-                        .append(true)
-                        .open(&file_name)
-                        .unwrap();
-
-                    // serialise to json
-                    let depth_order_book = serde_json::to_string(&depth_order_book).unwrap();
-                    let bytes_written = depth_order_book.as_bytes().len();
-
-                    let to_write = format!("{}\n", depth_order_book);
-                    file.write_all(to_write.as_bytes()).unwrap();
-
-                    // check if full order book correction is due
-                    let index = symbols
-                        .iter()
-                        .position(|s| **s == *symbol.to_lowercase())
-                        .unwrap();
-
-                    ticks_since_last_correction[index] += 1;
+            // streams are named <symbol>@<kind>
+            let (symbol, kind) = message.stream.split_once('@').unwrap_or((&message.stream, ""));
+            let symbol = symbol.to_uppercase();
+
+            let event = match kind {
+                "depth@100ms" => serde_json::from_value(message.data).map(EventType::PartialOrderBook),
+                "aggTrade" => serde_json::from_value(message.data).map(EventType::Trade),
+                "bookTicker" => serde_json::from_value(message.data).map(EventType::BookTicker),
+                "markPrice@1s" => serde_json::from_value(message.data).map(EventType::MarkPrice),
+                "forceOrder" => serde_json::from_value(message.data).map(EventType::Liquidation),
+                kind if kind.starts_with("kline_") => serde_json::from_value(message.data).map(EventType::Kline),
+                _ => {
+                    pb.println(format!("Unexpected stream {}", message.stream));
+                    continue;
+                }
+            };
 
-                    if ticks_since_last_correction[index] == CORRECTION_INTERVAL {
-                        let _ = tx.send(symbol.clone());
+            let event = match event {
+                Ok(event) => Event::received(symbol.clone(), message.receive_time_ns, event),
+                Err(e) => {
+                    pb.println(format!("Unexpected payload on {}: {}", message.stream, e));
+                    continue;
+                }
+            };
 
-                        ticks_since_last_correction[index] = 0;
-                    }
+            // append under {output_dir}/{symbol} directory
+            let Some(bytes_written) = write_or_stop(&writer, &event, &keep_running) else {
+                break;
+            };
 
-                    // increment runtime stats
-                    {
-                        let mut stats = runtime_stats.lock().unwrap();
-                        stats.n_data_points += 1;
-                        stats.bytes_written += bytes_written;
+            if let EventType::MarkPrice(mark) = &event.event {
+                // the rate charged at a funding time is the last one quoted before it
+                if let Some(rate) = funding.update(&symbol, mark) {
+                    let rate = Event::received(symbol.clone(), message.receive_time_ns, EventType::FundingRate(rate));
+                    if write_or_stop(&writer, &rate, &keep_running).is_none() {
+                        break;
                     }
                 }
-                WebsocketEvent::AccountUpdate(account_update) => {
-                    println!("Account Update: {:?}", account_update);
+            }
+
+            if let EventType::PartialOrderBook(diff) = &event.event {
+                // spot diffs follow on from the previous one's final id, futures
+                // diffs carry it as `pu`; a break means the book needs a snapshot
+                let gap = last_update_ids
+                    .insert(symbol.clone(), diff.final_update_id)
+                    .is_some_and(|last| match diff.previous_final_update_id {
+                        Some(previous) => previous != last,
+                        None => diff.first_update_id != last + 1,
+                    });
+
+                // check if full order book correction is due
+                let index = symbols
+                    .iter()
+                    .position(|s| **s == *symbol.to_lowercase())
+                    .unwrap();
+
+                ticks_since_last_correction[index] += 1;
+
+                if gap {
+                    runtime_stats.lock().unwrap().n_gaps += 1;
                 }
-                _ => panic!("Error: {:?}", event),
-            };
 
-            Ok(())
-        });
+                if gap || ticks_since_last_correction[index] >= CORRECTION_INTERVAL {
+                    let _ = tx.send(symbol.clone());
+
+                    ticks_since_last_correction[index] = 0;
+                }
+            }
 
-        web_socket.connect_multiple_streams(&depth).unwrap(); // check error
-        if let Err(e) = web_socket.event_loop(&keep_running) {
-            match e {
-                err => {
-                    println!("Error: {:?}", err);
+            // increment runtime stats
+            {
+                let mut stats = runtime_stats.lock().unwrap();
+                stats.n_data_points += 1;
+                stats.bytes_written += bytes_written;
+                if let EventType::Trade(_) = event.event {
+                    stats.n_trades += 1;
+                }
+                *stats.messages.entry(symbol).or_default() += 1;
+                if let Some(latency) = event.feed_latency() {
+                    stats.latency.record(latency);
                 }
             }
         }
 
+        if !keep_running.load(Ordering::Relaxed) {
+            break;
+        }
+
         println!("Reconnecting in 5 seconds...");
+        runtime_stats.lock().unwrap().n_reconnects += 1;
         tokio::time::sleep(Duration::from_secs(5)).await;
     }
-    // handle.await.unwrap();
+
+    // write out everything still held in the reorder window before exiting
+    writer.shutdown();
+    match task::block_in_place(|| writer_thread.join()) {
+        Ok(Ok(())) => pb.finish(),
+        // the writer thread has already said what went wrong
+        Ok(Err(_)) | Err(_) => std::process::exit(1),
+    }
 }
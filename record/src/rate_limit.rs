@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+// never plan to use more than this fraction of the limit ourselves
+const WEIGHT_SAFETY_MARGIN: f64 = 0.8;
+// used when a 429/418 arrives without a Retry-After header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Snapshot of the limiter, cheap enough to copy into `RunTimeStats`.
+#[derive(Clone, Copy, Default)]
+pub struct RateLimitState {
+    pub used_weight: u32,
+    pub weight_limit: u32,
+    pub n_rate_limited: usize,
+    pub n_banned: usize,
+    pub n_rest_errors: usize,
+    pub backoff_remaining: Duration,
+}
+
+/// Tracks the request weight used in the current minute and any back-off
+/// the exchange has asked us to respect.
+pub struct RateLimiter {
+    weight_limit: u32,
+    used_weight: u32,
+    window: u64,
+    blocked_until: Option<Instant>,
+    n_rate_limited: usize,
+    n_banned: usize,
+    n_rest_errors: usize,
+}
+
+impl RateLimiter {
+    pub fn new(weight_limit: u32) -> Self {
+        Self {
+            weight_limit,
+            used_weight: 0,
+            window: Self::current_window(),
+            blocked_until: None,
+            n_rate_limited: 0,
+            n_banned: 0,
+            n_rest_errors: 0,
+        }
+    }
+
+    // binance counts weight per calendar minute
+    fn current_window() -> u64 {
+        chrono::Utc::now().timestamp_millis() as u64 / 60_000
+    }
+
+    fn time_to_next_window() -> Duration {
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        Duration::from_millis(60_000 - now % 60_000)
+    }
+
+    fn roll_window(&mut self) {
+        let window = Self::current_window();
+        if window != self.window {
+            self.window = window;
+            self.used_weight = 0;
+        }
+    }
+
+    /// Try to reserve `weight` for a request.
+    ///
+    /// Returns `Duration::ZERO` if the request may be sent now, otherwise how
+    /// long to wait before asking again.
+    pub fn acquire(&mut self, weight: u32) -> Duration {
+        self.roll_window();
+
+        if let Some(blocked_until) = self.blocked_until {
+            let now = Instant::now();
+            if blocked_until > now {
+                return blocked_until - now;
+            }
+            self.blocked_until = None;
+        }
+
+        let budget = (self.weight_limit as f64 * WEIGHT_SAFETY_MARGIN) as u32;
+        if self.used_weight + weight > budget {
+            return Self::time_to_next_window();
+        }
+
+        self.used_weight += weight;
+        Duration::ZERO
+    }
+
+    /// Record the weight the exchange reports as used this minute.
+    pub fn observe_used_weight(&mut self, used_weight: u32) {
+        self.roll_window();
+        self.used_weight = used_weight;
+    }
+
+    /// Stop sending requests after a 429 (or a 418 IP ban if `banned`).
+    pub fn back_off(&mut self, retry_after: Option<Duration>, banned: bool) {
+        if banned {
+            self.n_banned += 1;
+        } else {
+            self.n_rate_limited += 1;
+        }
+
+        let until = Instant::now() + retry_after.unwrap_or(DEFAULT_RETRY_AFTER);
+        self.blocked_until = Some(self.blocked_until.map_or(until, |b| b.max(until)));
+    }
+
+    pub fn record_error(&mut self) {
+        self.n_rest_errors += 1;
+    }
+
+    pub fn state(&self) -> RateLimitState {
+        RateLimitState {
+            used_weight: self.used_weight,
+            weight_limit: self.weight_limit,
+            n_rate_limited: self.n_rate_limited,
+            n_banned: self.n_banned,
+            n_rest_errors: self.n_rest_errors,
+            backoff_remaining: self
+                .blocked_until
+                .map_or(Duration::ZERO, |b| b.saturating_duration_since(Instant::now())),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt::{self, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use binance::model::{OrderBook, ServerTime};
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, Response};
+use serde::de::DeserializeOwned;
+
+use crate::exchange_info::ExchangeInfo;
+use crate::rate_limit::RateLimiter;
+
+#[derive(Debug)]
+pub enum RestError {
+    // 429: we sent too much weight and have to wait
+    RateLimited(Option<Duration>),
+    // 418: the IP has been banned for ignoring 429s
+    Banned(Option<Duration>),
+    Status(StatusCode, String),
+    Transport(reqwest::Error),
+}
+
+impl Display for RestError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RestError::RateLimited(retry_after) => write!(f, "rate limited (retry after {:?})", retry_after),
+            RestError::Banned(retry_after) => write!(f, "IP banned (retry after {:?})", retry_after),
+            RestError::Status(status, body) => write!(f, "{}: {}", status, body),
+            RestError::Transport(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for RestError {
+    fn from(e: reqwest::Error) -> Self {
+        RestError::Transport(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Market {
+    Spot,
+    // USDⓈ-M futures
+    Futures,
+}
+
+impl Market {
+    // request weight allowed per IP per minute
+    pub fn weight_limit(self) -> u32 {
+        match self {
+            Market::Spot => 6000,
+            Market::Futures => 2400,
+        }
+    }
+
+    fn path(self, endpoint: &str) -> String {
+        match self {
+            Market::Spot => format!("/api/v3/{}", endpoint),
+            Market::Futures => format!("/fapi/v1/{}", endpoint),
+        }
+    }
+
+    // https://developers.binance.com/docs/binance-spot-api-docs/rest-api/market-data-endpoints#order-book
+    // https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Order-Book
+    fn depth_weight(self, limit: u64) -> u32 {
+        match (self, limit) {
+            (Market::Spot, 0..=100) => 5,
+            (Market::Spot, 101..=500) => 25,
+            (Market::Spot, 501..=1000) => 50,
+            (Market::Spot, _) => 250,
+            (Market::Futures, 0..=50) => 2,
+            (Market::Futures, 51..=100) => 5,
+            (Market::Futures, 101..=500) => 10,
+            (Market::Futures, _) => 20,
+        }
+    }
+
+    fn exchange_info_weight(self) -> u32 {
+        match self {
+            Market::Spot => 20,
+            Market::Futures => 1,
+        }
+    }
+}
+
+/// Blocking REST client that accounts every request against a shared
+/// [`RateLimiter`], waiting out the limiter instead of hitting the exchange.
+pub struct RestClient {
+    client: Client,
+    base_url: String,
+    market: Market,
+    limiter: Arc<Mutex<RateLimiter>>,
+}
+
+impl RestClient {
+    pub fn new(base_url: String, market: Market, limiter: Arc<Mutex<RateLimiter>>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+            market,
+            limiter,
+        }
+    }
+
+    pub fn get_depth(&self, symbol: &str, limit: u64) -> Result<OrderBook, RestError> {
+        let query = format!("symbol={}&limit={}", symbol.to_uppercase(), limit);
+        self.get(&self.market.path("depth"), &query, self.market.depth_weight(limit))
+    }
+
+    pub fn get_exchange_info(&self) -> Result<ExchangeInfo, RestError> {
+        self.get(&self.market.path("exchangeInfo"), "", self.market.exchange_info_weight())
+    }
+
+    pub fn get_server_time(&self) -> Result<ServerTime, RestError> {
+        self.get(&self.market.path("time"), "", 1)
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str, query: &str, weight: u32) -> Result<T, RestError> {
+        loop {
+            let wait = self.limiter.lock().unwrap().acquire(weight);
+            if wait.is_zero() {
+                break;
+            }
+            std::thread::sleep(wait);
+        }
+
+        let url = format!("{}{}?{}", self.base_url, path, query);
+        let result = self.client.get(url).send();
+
+        let mut limiter = self.limiter.lock().unwrap();
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                limiter.record_error();
+                return Err(e.into());
+            }
+        };
+
+        if let Some(used_weight) = header_u64(&response, "x-mbx-used-weight-1m") {
+            limiter.observe_used_weight(used_weight as u32);
+        }
+
+        let retry_after = header_u64(&response, "retry-after").map(Duration::from_secs);
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::TOO_MANY_REQUESTS => {
+                limiter.back_off(retry_after, false);
+                return Err(RestError::RateLimited(retry_after));
+            }
+            StatusCode::IM_A_TEAPOT => {
+                limiter.back_off(retry_after, true);
+                return Err(RestError::Banned(retry_after));
+            }
+            status => {
+                limiter.record_error();
+                return Err(RestError::Status(status, response.text().unwrap_or_default()));
+            }
+        }
+        drop(limiter);
+
+        response.json::<T>().map_err(|e| {
+            self.limiter.lock().unwrap().record_error();
+            e.into()
+        })
+    }
+}
+
+fn header_u64(response: &Response, name: &str) -> Option<u64> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
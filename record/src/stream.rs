@@ -0,0 +1,81 @@
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket, connect};
+
+// tungstenite's error is large, keep it off the stack of the hot read path
+pub type Result<T> = std::result::Result<T, Box<tungstenite::Error>>;
+
+// how long a read blocks before the caller gets to check whether to stop
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+// https://developers.binance.com/docs/binance-spot-api-docs/web-socket-streams#general-wss-information
+#[derive(Deserialize)]
+struct CombinedMessage {
+    stream: String,
+    data: serde_json::Value,
+}
+
+/// A payload from one of the subscribed streams.
+pub struct StreamMessage {
+    // taken straight after the frame comes off the socket, before any parsing
+    pub receive_time_ns: u64,
+    pub stream: String,
+    pub data: serde_json::Value,
+}
+
+/// Connection to the exchange's combined stream endpoint.
+pub struct MarketStream {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+}
+
+impl MarketStream {
+    pub fn connect(ws_endpoint: &str, streams: &[String]) -> Result<Self> {
+        let url = format!("{}/stream?streams={}", ws_endpoint, streams.join("/"));
+        let (socket, _) = connect(url)?;
+
+        let timeout = match socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(READ_TIMEOUT)),
+            MaybeTlsStream::NativeTls(stream) => stream.get_ref().set_read_timeout(Some(READ_TIMEOUT)),
+            _ => Ok(()),
+        };
+        timeout.map_err(tungstenite::Error::Io)?;
+
+        Ok(Self { socket })
+    }
+
+    /// Block until the next stream payload arrives, or `None` if nothing came
+    /// in within the read timeout.
+    ///
+    /// Pings are answered by tungstenite while reading, anything that is not a
+    /// combined stream payload is skipped.
+    pub fn next(&mut self) -> Result<Option<StreamMessage>> {
+        loop {
+            let message = match self.socket.read() {
+                Ok(message) => message,
+                Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(None);
+                }
+                Err(e) => return Err(Box::new(e)),
+            };
+            let receive_time_ns = datatypes::now_ns();
+
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => return Err(Box::new(tungstenite::Error::ConnectionClosed)),
+                _ => continue,
+            };
+
+            if let Ok(combined) = serde_json::from_str::<CombinedMessage>(&text) {
+                return Ok(Some(StreamMessage {
+                    receive_time_ns,
+                    stream: combined.stream,
+                    data: combined.data,
+                }));
+            }
+        }
+    }
+}
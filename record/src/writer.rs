@@ -0,0 +1,213 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use datatypes::Event;
+
+#[derive(Clone, Copy)]
+pub struct WriterConfig {
+    // how long an event is held back so late arrivals can be put in front of it
+    pub reorder_window: Duration,
+    pub flush_interval: Duration,
+    pub fsync_interval: Duration,
+}
+
+enum WriterMessage {
+    Line { file: String, receive_time: u64, line: String },
+    Shutdown,
+}
+
+/// Handle for appending events to the tape.
+///
+/// Every `{symbol}.json` file is owned by a single writer thread, so callers on
+/// any thread can write without reopening files or interleaving partial lines.
+#[derive(Clone)]
+pub struct TapeWriter {
+    tx: Sender<WriterMessage>,
+    shutting_down: Arc<AtomicBool>,
+}
+
+impl TapeWriter {
+    pub fn spawn(output_dir: String, config: WriterConfig) -> (Self, JoinHandle<io::Result<()>>) {
+        let (tx, rx) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            let result = run(rx, &output_dir, config);
+            // callers only see a closed channel, say why as soon as it happens
+            if let Err(e) = &result {
+                eprintln!("Tape writer stopped: {}", e);
+            }
+            result
+        });
+
+        let writer = Self {
+            tx,
+            shutting_down: Arc::new(AtomicBool::new(false)),
+        };
+
+        (writer, handle)
+    }
+
+    /// Queue an event for `{output_dir}/{symbol}.json`, returning the number of bytes it takes up.
+    ///
+    /// Fails once the writer has stopped, after an I/O error or [`TapeWriter::shutdown`],
+    /// the event is not written.
+    pub fn write(&self, event: &Event) -> io::Result<usize> {
+        if self.shutting_down.load(Ordering::Relaxed) {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "tape writer is shutting down"));
+        }
+
+        let mut line = serde_json::to_string(event)?;
+        let bytes_written = line.len();
+        // a line goes to the file in a single write, newline included
+        line.push('\n');
+
+        self.tx
+            .send(WriterMessage::Line {
+                file: event.symbol.clone(),
+                receive_time: event.receive_time,
+                line,
+            })
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "tape writer has stopped"))?;
+
+        Ok(bytes_written)
+    }
+
+    /// Ask the writer thread to write out everything it holds and stop.
+    ///
+    /// Writes made after this fail.
+    pub fn shutdown(&self) {
+        self.shutting_down.store(true, Ordering::Relaxed);
+        let _ = self.tx.send(WriterMessage::Shutdown);
+    }
+}
+
+// owns every tape file until shutdown or the first I/O error
+fn run(rx: Receiver<WriterMessage>, output_dir: &str, config: WriterConfig) -> io::Result<()> {
+    let mut files: HashMap<String, TapeFile> = HashMap::new();
+    let mut last_flush = Instant::now();
+    let mut last_fsync = Instant::now();
+
+    loop {
+        let message = match rx.recv_timeout(config.flush_interval) {
+            Ok(message) => Some(message),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(WriterMessage::Shutdown),
+        };
+
+        match message {
+            Some(WriterMessage::Line { file, receive_time, line }) => {
+                if !files.contains_key(&file) {
+                    let path = format!("{}/{}.json", output_dir, file);
+                    files.insert(file.clone(), TapeFile::open(&path)?);
+                }
+                files.get_mut(&file).unwrap().push(receive_time, line);
+            }
+            Some(WriterMessage::Shutdown) => {
+                for tape_file in files.values_mut() {
+                    tape_file.release(u64::MAX)?;
+                    tape_file.writer.flush()?;
+                    tape_file.writer.get_ref().sync_data()?;
+                }
+                return Ok(());
+            }
+            None => {}
+        }
+
+        if last_flush.elapsed() >= config.flush_interval {
+            let now = chrono::Utc::now().timestamp_millis() as u64;
+            let watermark = now.saturating_sub(config.reorder_window.as_millis() as u64);
+
+            for tape_file in files.values_mut() {
+                tape_file.release(watermark)?;
+                tape_file.writer.flush()?;
+            }
+            last_flush = Instant::now();
+        }
+
+        if last_fsync.elapsed() >= config.fsync_interval {
+            for tape_file in files.values_mut() {
+                tape_file.writer.get_ref().sync_data()?;
+            }
+            last_fsync = Instant::now();
+        }
+    }
+}
+
+struct TapeFile {
+    writer: BufWriter<File>,
+    // lines waiting out the reorder window, oldest first
+    pending: BinaryHeap<Reverse<(u64, u64, String)>>,
+    sequence: u64,
+}
+
+impl TapeFile {
+    fn open(path: &str) -> io::Result<Self> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+
+        truncate_partial_line(&mut file)?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+            pending: BinaryHeap::new(),
+            sequence: 0,
+        })
+    }
+
+    fn push(&mut self, receive_time: u64, line: String) {
+        // the sequence number keeps lines with equal receive times in arrival order
+        self.pending.push(Reverse((receive_time, self.sequence, line)));
+        self.sequence += 1;
+    }
+
+    // write out every pending line received at or before `watermark`
+    fn release(&mut self, watermark: u64) -> io::Result<()> {
+        while let Some(Reverse((receive_time, _, _))) = self.pending.peek() {
+            if *receive_time > watermark {
+                break;
+            }
+
+            let Reverse((_, _, line)) = self.pending.pop().unwrap();
+            self.writer.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+// a crash mid-write can leave half a JSON line at the end of the file,
+// cut it off so the next line we append starts cleanly
+fn truncate_partial_line(file: &mut File) -> io::Result<()> {
+    const CHUNK: u64 = 64 * 1024;
+
+    let len = file.metadata()?.len();
+    let mut end = len;
+
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK);
+        let mut buf = vec![0; (end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut buf)?;
+
+        if let Some(i) = buf.iter().rposition(|&b| b == b'\n') {
+            let keep = start + i as u64 + 1;
+            if keep != len {
+                file.set_len(keep)?;
+            }
+            return Ok(());
+        }
+        end = start;
+    }
+
+    // no complete line at all
+    file.set_len(0)
+}
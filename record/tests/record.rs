@@ -0,0 +1,184 @@
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use datatypes::EventType;
+use mock_exchange::testing::{count, output_dir, read_tape, wait_for};
+use mock_exchange::{MockExchange, Scenario};
+use serde_json::{json, Value};
+
+fn start_record(mock: &MockExchange, dir: &Path) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_record"))
+        .arg(dir)
+        .args(["--rest-endpoint", &mock.rest_url()])
+        .args(["--ws-endpoint", &mock.ws_url()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap()
+}
+
+// SIGTERM so the recorder writes out everything it is holding
+fn stop_record(mut record: Child) {
+    Command::new("kill")
+        .args(["-TERM", &record.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(record.wait().unwrap().success());
+}
+
+fn is_diff(event: &EventType) -> bool {
+    matches!(event, EventType::PartialOrderBook(_))
+}
+
+fn is_full_book(event: &EventType) -> bool {
+    matches!(event, EventType::FullOrderBook(_))
+}
+
+fn diff(first: u64, last: u64) -> Value {
+    json!({
+        "type": "message",
+        "stream": "btcusdt@depth@100ms",
+        "data": {"e": "depthUpdate", "E": 1_700_000_000_000u64 + last, "s": "BTCUSDT", "U": first, "u": last, "b": [["100.0", "2.0"]], "a": []},
+        "delay_ms": 50
+    })
+}
+
+fn snapshot(last_update_id: u64) -> Value {
+    json!({"lastUpdateId": last_update_id, "bids": [["100.0", "1.0"]], "asks": [["101.0", "1.0"]]})
+}
+
+fn scenario(value: Value) -> Scenario {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn records_depth_and_trades() {
+    let mock = MockExchange::start(scenario(json!({
+        "market": [
+            diff(101, 102),
+            {
+                "type": "message",
+                "stream": "btcusdt@aggTrade",
+                "data": {"e": "aggTrade", "E": 1_700_000_000_103u64, "s": "BTCUSDT", "a": 1, "p": "100.5", "q": "0.1", "f": 1, "l": 1, "T": 1_700_000_000_103u64, "m": false, "M": true}
+            },
+            diff(103, 104),
+        ]
+    })))
+    .unwrap();
+
+    let dir = output_dir("depth");
+    let record = start_record(&mock, &dir);
+    let tape = dir.join("BTCUSDT.json");
+    wait_for(&tape, |events| events.len() >= 3);
+    stop_record(record);
+
+    let events = read_tape(&tape);
+    assert_eq!(count(&events, is_diff), 2);
+    assert_eq!(count(&events, |e| matches!(e, EventType::Trade(_))), 1);
+    assert!(events.iter().all(|e| e.receive_time_ns.is_some() && e.exchange_time.is_some()));
+    assert!(events.windows(2).all(|w| w[0].receive_time <= w[1].receive_time));
+}
+
+#[test]
+fn gap_triggers_snapshot() {
+    let mock = MockExchange::start(scenario(json!({
+        "depth": {"BTCUSDT": [snapshot(110)]},
+        "market": [diff(101, 102), diff(103, 104), diff(108, 110)]
+    })))
+    .unwrap();
+
+    let dir = output_dir("gap");
+    let record = start_record(&mock, &dir);
+    let tape = dir.join("BTCUSDT.json");
+    wait_for(&tape, |events| count(events, is_full_book) == 1);
+    stop_record(record);
+
+    let depth_requests = mock
+        .requests()
+        .into_iter()
+        .filter(|request| request.path == "/api/v3/depth")
+        .collect::<Vec<_>>();
+    assert_eq!(depth_requests.len(), 1);
+    assert!(depth_requests[0].query.contains("symbol=BTCUSDT"));
+
+    // the snapshot is ordered after the diff that showed the gap
+    let events = read_tape(&tape);
+    let snapshot_at = events.iter().position(|e| is_full_book(&e.event)).unwrap();
+    assert_eq!(count(&events[..snapshot_at], is_diff), 3);
+}
+
+#[test]
+fn waits_out_retry_after() {
+    let mock = MockExchange::start(scenario(json!({
+        "depth": {"BTCUSDT": [snapshot(120)]},
+        "depth_replies": [{"status": 429, "retry_after": 2}],
+        "market": [diff(101, 102), diff(105, 106), diff(110, 112)]
+    })))
+    .unwrap();
+
+    let dir = output_dir("rate-limit");
+    let record = start_record(&mock, &dir);
+    let tape = dir.join("BTCUSDT.json");
+    wait_for(&tape, |events| count(events, is_full_book) == 1);
+    stop_record(record);
+
+    let depth_requests = mock
+        .requests()
+        .into_iter()
+        .filter(|request| request.path == "/api/v3/depth")
+        .collect::<Vec<_>>();
+    assert_eq!(depth_requests.len(), 2);
+    assert!(depth_requests[1].time - depth_requests[0].time >= Duration::from_secs(2));
+}
+
+#[test]
+fn reconnects_after_disconnect() {
+    let mock = MockExchange::start(scenario(json!({
+        "market": [diff(101, 102), {"type": "disconnect"}, diff(103, 104)]
+    })))
+    .unwrap();
+
+    let dir = output_dir("reconnect");
+    let record = start_record(&mock, &dir);
+    let tape = dir.join("BTCUSDT.json");
+    wait_for(&tape, |events| count(events, is_diff) == 2);
+    stop_record(record);
+
+    let market_connections = mock
+        .connections()
+        .into_iter()
+        .filter(|path| path.starts_with("/stream"))
+        .count();
+    assert_eq!(market_connections, 2);
+}
+
+#[test]
+fn ignores_account_endpoints() {
+    let mock = MockExchange::start(scenario(json!({
+        "depth": {"BTCUSDT": [snapshot(110)]},
+        "market": [diff(101, 102), diff(108, 110)]
+    })))
+    .unwrap();
+
+    // nothing listens here, a recorder that followed these would never get a book
+    let elsewhere = "127.0.0.1:9";
+    let dir = output_dir("env");
+    let record = Command::new(env!("CARGO_BIN_EXE_record"))
+        .arg(&dir)
+        .env("RECORD_REST_ENDPOINT", mock.rest_url())
+        .env("RECORD_WS_ENDPOINT", mock.ws_url())
+        .env("ACCOUNT_REST_ENDPOINT", format!("http://{}", elsewhere))
+        .env("ACCOUNT_WS_ENDPOINT", format!("ws://{}", elsewhere))
+        .env("REST_ENDPOINT", format!("http://{}", elsewhere))
+        .env("WS_ENDPOINT", format!("ws://{}", elsewhere))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let tape = dir.join("BTCUSDT.json");
+    wait_for(&tape, |events| count(events, is_diff) == 2 && count(events, is_full_book) == 1);
+    stop_record(record);
+
+    assert!(mock.requests().iter().any(|request| request.path == "/api/v3/depth"));
+}
@@ -0,0 +1,98 @@
+mod replay;
+mod rest;
+mod streams;
+
+use std::io;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use clap::Parser;
+use interleave::{Interleaver, OrderBy};
+use mock_exchange::http;
+use replay::{Replay, ReplayClock};
+
+// longest the player sleeps before looking at the clock again, so speed
+// changes take effect promptly
+const MAX_SLEEP: Duration = Duration::from_millis(100);
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Paths to the data directories to replay
+    #[arg(required = true)]
+    paths: Vec<String>,
+
+    /// Which timestamp to merge the files by and replay on
+    #[arg(long, value_enum, default_value_t = OrderBy::Receive)]
+    order_by: OrderBy,
+
+    /// Shift each directory's receive times onto the exchange clock
+    #[arg(long)]
+    correct_clock: bool,
+
+    /// Replay speed as a multiple of real time
+    #[arg(long, default_value_t = 1.0)]
+    speed: f64,
+
+    /// Address to serve REST on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    rest_addr: String,
+
+    /// Address to serve websockets on
+    #[arg(long, default_value = "127.0.0.1:8081")]
+    ws_addr: String,
+}
+
+fn main() -> io::Result<()> {
+    let args = Args::parse();
+
+    let mut events = Interleaver::open(&args.paths, args.order_by, args.correct_clock)?.peekable();
+    let Some(first) = events.peek() else {
+        eprintln!("Nothing to replay");
+        return Ok(());
+    };
+
+    let clock = ReplayClock::new(args.order_by.key(first), args.speed);
+    let replay = Arc::new(Mutex::new(Replay::new(clock)));
+
+    let rest_listener = TcpListener::bind(&args.rest_addr)?;
+    let ws_listener = TcpListener::bind(&args.ws_addr)?;
+    println!("RECORD_REST_ENDPOINT=http://{}", rest_listener.local_addr()?);
+    println!("RECORD_WS_ENDPOINT=ws://{}", ws_listener.local_addr()?);
+
+    let rest_replay = replay.clone();
+    std::thread::spawn(move || http::serve(rest_listener, move |request| rest::handle(&rest_replay, request)));
+    let ws_replay = replay.clone();
+    std::thread::spawn(move || streams::serve(ws_listener, ws_replay));
+
+    for event in events {
+        let time = args.order_by.key(&event);
+
+        loop {
+            let (now, speed) = {
+                let replay = replay.lock().unwrap();
+                (replay.clock.now(), replay.clock.speed())
+            };
+            if now >= time {
+                break;
+            }
+
+            let wait = if speed > 0.0 {
+                Duration::from_millis(((time - now) as f64 / speed) as u64).min(MAX_SLEEP)
+            } else {
+                MAX_SLEEP
+            };
+            std::thread::sleep(wait);
+        }
+
+        replay.lock().unwrap().play(&event, time);
+    }
+
+    // keep serving the final books until stopped
+    replay.lock().unwrap().finished = true;
+    eprintln!("Replay finished");
+    loop {
+        std::thread::park();
+    }
+}
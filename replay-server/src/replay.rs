@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+use std::time::Instant;
+
+use binance::model::OrderBook;
+use datatypes::partial_transformer::update_full_order_book;
+use datatypes::{Event, EventType, SymbolInfo};
+use mock_exchange::scenario::market_stream;
+use serde_json::json;
+
+/// Tape time that runs at `speed` times wall clock time, 0 pauses it.
+pub struct ReplayClock {
+    wall_anchor: Instant,
+    tape_anchor: u64,
+    speed: f64,
+}
+
+impl ReplayClock {
+    pub fn new(tape_start: u64, speed: f64) -> Self {
+        Self {
+            wall_anchor: Instant::now(),
+            tape_anchor: tape_start,
+            speed,
+        }
+    }
+
+    /// Current tape time in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.tape_anchor + (self.wall_anchor.elapsed().as_millis() as f64 * self.speed) as u64
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        self.tape_anchor = self.now();
+        self.wall_anchor = Instant::now();
+        self.speed = speed;
+    }
+}
+
+/// A websocket client and the streams it asked for.
+pub struct Subscriber {
+    pub streams: Vec<String>,
+    // `/stream?streams=` clients get `{"stream": .., "data": ..}`, `/ws/` clients the bare payload
+    pub combined: bool,
+    pub tx: Sender<String>,
+}
+
+/// What the replay has reached, shared between the player and the servers.
+pub struct Replay {
+    pub clock: ReplayClock,
+    // key of the last event played
+    pub played_to: u64,
+    pub finished: bool,
+    pub books: HashMap<String, OrderBook>,
+    pub symbol_info: HashMap<String, SymbolInfo>,
+    pub subscribers: Vec<Subscriber>,
+}
+
+impl Replay {
+    pub fn new(clock: ReplayClock) -> Self {
+        Self {
+            played_to: clock.now(),
+            clock,
+            finished: false,
+            books: HashMap::new(),
+            symbol_info: HashMap::new(),
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Bring the books up to date with an event and send it to whoever subscribed.
+    pub fn play(&mut self, event: &Event, time: u64) {
+        self.played_to = time;
+
+        match &event.event {
+            EventType::FullOrderBook(ob) => {
+                self.books.insert(event.symbol.clone(), ob.clone());
+            }
+            EventType::PartialOrderBook(dob) => {
+                if let Some(ob) = self.books.get_mut(&event.symbol) {
+                    if dob.final_update_id > ob.last_update_id {
+                        update_full_order_book(ob, dob);
+                    }
+                }
+            }
+            EventType::SymbolInfo(info) => {
+                self.symbol_info.insert(event.symbol.clone(), info.clone());
+            }
+            _ => (),
+        }
+
+        let Some((stream, data)) = market_stream(event) else {
+            return;
+        };
+        let combined = json!({"stream": stream, "data": data}).to_string();
+        let data = data.to_string();
+
+        // a failed send means the client has gone
+        self.subscribers.retain(|subscriber| {
+            if !subscriber.streams.contains(&stream) {
+                return true;
+            }
+            let payload = if subscriber.combined { &combined } else { &data };
+            subscriber.tx.send(payload.clone()).is_ok()
+        });
+    }
+}
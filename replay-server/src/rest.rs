@@ -0,0 +1,99 @@
+use std::sync::Mutex;
+
+use datatypes::SymbolInfo;
+use mock_exchange::http::{Request, Response};
+use mock_exchange::scenario::depth_snapshot;
+use serde_json::{json, Value};
+
+use crate::replay::Replay;
+
+const DEFAULT_DEPTH_LIMIT: usize = 100;
+
+fn invalid_symbol() -> Response {
+    (400, None, json!({"code": -1121, "msg": "Invalid symbol."}))
+}
+
+// back into the exchange info filters `record` reads the rules from
+fn exchange_symbol(symbol: &str, info: &SymbolInfo) -> Value {
+    let price = |value: f64| format!("{:.*}", info.price_decimals as usize, value);
+    let qty = |value: f64| format!("{:.*}", info.qty_decimals as usize, value);
+
+    let mut filters = vec![
+        json!({"filterType": "PRICE_FILTER", "minPrice": price(info.min_price), "maxPrice": price(info.max_price), "tickSize": price(info.tick_size)}),
+        json!({"filterType": "LOT_SIZE", "minQty": qty(info.min_qty), "maxQty": qty(info.max_qty), "stepSize": qty(info.step_size)}),
+    ];
+    if let Some(min_notional) = info.min_notional {
+        filters.push(json!({"filterType": "NOTIONAL", "minNotional": min_notional.to_string()}));
+    }
+
+    json!({
+        "symbol": symbol,
+        "status": info.status,
+        "baseAsset": info.base_asset,
+        "quoteAsset": info.quote_asset,
+        "filters": filters
+    })
+}
+
+pub fn handle(replay: &Mutex<Replay>, request: &Request) -> Response {
+    let mut replay = replay.lock().unwrap();
+
+    if request.path == "/replay" {
+        return (
+            200,
+            None,
+            json!({"clock": replay.played_to, "speed": replay.clock.speed(), "finished": replay.finished}),
+        );
+    }
+    if request.path == "/replay/speed" {
+        return match request.query.get("speed").and_then(|speed| speed.parse::<f64>().ok()) {
+            Some(speed) if speed >= 0.0 => {
+                replay.clock.set_speed(speed);
+                (200, None, json!({"speed": speed}))
+            }
+            _ => (400, None, json!({"code": -1102, "msg": "speed must be a number >= 0"})),
+        };
+    }
+
+    // spot and futures endpoints are served the same way
+    let endpoint = request
+        .path
+        .strip_prefix("/api/v3/")
+        .or_else(|| request.path.strip_prefix("/fapi/v1/"))
+        .unwrap_or_default();
+
+    match endpoint {
+        "depth" => {
+            let Some(symbol) = request.query.get("symbol") else {
+                return invalid_symbol();
+            };
+            let Some(ob) = replay.books.get(symbol) else {
+                return invalid_symbol();
+            };
+
+            let limit = request
+                .query
+                .get("limit")
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(DEFAULT_DEPTH_LIMIT);
+            let mut ob = ob.clone();
+            ob.bids.truncate(limit);
+            ob.asks.truncate(limit);
+            (200, None, depth_snapshot(&ob))
+        }
+        "time" => (200, None, json!({"serverTime": replay.played_to})),
+        "exchangeInfo" => {
+            let symbols = replay
+                .symbol_info
+                .iter()
+                .map(|(symbol, info)| exchange_symbol(symbol, info))
+                .collect::<Vec<Value>>();
+            (
+                200,
+                None,
+                json!({"timezone": "UTC", "serverTime": replay.played_to, "rateLimits": [], "symbols": symbols}),
+            )
+        }
+        _ => (404, None, json!({"code": -1, "msg": "Not found."})),
+    }
+}
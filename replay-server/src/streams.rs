@@ -0,0 +1,93 @@
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tungstenite::handshake::server::{Request, Response};
+use tungstenite::{accept_hdr, Message};
+
+use crate::replay::{Replay, Subscriber};
+
+// read often enough to answer pings and notice closes between sends
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// the exchange's short names for the streams `record` subscribes to
+fn canonical_stream(stream: &str) -> String {
+    if let Some(symbol) = stream.strip_suffix("@depth") {
+        return format!("{}@depth@100ms", symbol);
+    }
+    if let Some(symbol) = stream.strip_suffix("@markPrice") {
+        return format!("{}@markPrice@1s", symbol);
+    }
+    stream.to_string()
+}
+
+// `/stream?streams=a/b` is a combined stream, `/ws/a/b` a raw one
+fn parse_path(path: &str) -> Option<(Vec<String>, bool)> {
+    let (streams, combined) = if let Some(streams) = path.strip_prefix("/stream?streams=") {
+        (streams, true)
+    } else {
+        (path.strip_prefix("/ws/")?, false)
+    };
+
+    let streams = streams.split('/').filter(|s| !s.is_empty()).map(canonical_stream).collect();
+    Some((streams, combined))
+}
+
+// the handshake callback's error type is tungstenite's, not ours to shrink
+#[allow(clippy::result_large_err)]
+fn run(stream: TcpStream, replay: Arc<Mutex<Replay>>) {
+    let mut path = String::new();
+    let mut socket = match accept_hdr(stream, |request: &Request, response: Response| {
+        path = request.uri().to_string();
+        Ok(response)
+    }) {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+
+    let Some((streams, combined)) = parse_path(&path) else {
+        let _ = socket.close(None);
+        return;
+    };
+
+    let (tx, rx) = mpsc::channel();
+    replay.lock().unwrap().subscribers.push(Subscriber { streams, combined, tx });
+
+    let _ = socket.get_ref().set_read_timeout(Some(Duration::from_millis(1)));
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(payload) => {
+                if socket.send(Message::text(payload)).is_err() {
+                    return;
+                }
+                // catch up on a backlog before checking the socket
+                while let Ok(payload) = rx.try_recv() {
+                    if socket.send(Message::text(payload)).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        match socket.read() {
+            Ok(Message::Close(_)) => return,
+            Ok(_) => (),
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => (),
+            Err(_) => return,
+        }
+    }
+}
+
+pub fn serve(listener: TcpListener, replay: Arc<Mutex<Replay>>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let replay = replay.clone();
+        std::thread::spawn(move || run(stream, replay));
+    }
+}
@@ -1,18 +1,27 @@
 use color_eyre::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind};
+use crate::blotter::Blotter;
+use crate::charts::Charts;
+use crate::event_list::EventList;
+use crate::health::RecorderHealth;
+use crate::ladder::Ladder;
+use crate::layout::{Panel, PanelLayout};
+use crate::overview::Overview;
+use crate::player::{self, Playback, Player};
+use crate::portfolio::Portfolio;
+use crate::source::{Follower, Source};
 use datatypes::{EventType, world_builder::World};
+use interleave::{Interleaver, OrderBy};
 use ratatui::{
     DefaultTerminal, Frame,
-    layout::{Constraint, Direction, Layout},
-    style::Stylize,
-    symbols::line,
-    text::Line,
-    widgets::{Block, Clear, Paragraph},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Borders, Clear, Paragraph},
 };
 use std::{
-    collections::HashMap,
-    fs::File,
-    io::{self, BufRead, BufReader},
+    io::BufRead,
+    sync::mpsc::Sender,
+    sync::{Arc, Mutex},
     sync::mpsc::{Receiver, channel},
     thread,
     time::Duration,
@@ -21,12 +30,29 @@ use std::{
 #[derive(Debug)]
 pub struct App {
     running: bool,
+    source: Source,
 
-    partials: Vec<datatypes::Event>,
-    fulls: Vec<datatypes::Event>,
-    orders: Vec<datatypes::Event>,
-    fills: Vec<datatypes::Event>,
+    partials: EventList,
+    fulls: EventList,
+    events: EventList,
     worlds: Vec<datatypes::world_builder::World>,
+    ladder: Ladder,
+    charts: Charts,
+    blotter: Blotter,
+    health: RecorderHealth,
+    portfolio: Portfolio,
+    layout: PanelLayout,
+    // where each panel went in the last frame, for the mouse
+    areas: Vec<(Panel, Rect)>,
+    // the pretty printed event being looked at and how far it's scrolled
+    detail: Option<(String, u16)>,
+    overview: Overview,
+    // only when playing back a recording
+    playback: Option<Arc<Mutex<Playback>>>,
+    // the time being typed in to jump to
+    jump: Option<String>,
+    // the `tail`s behind a files source, stopped along with the app
+    follower: Option<Follower>,
 
     event_stream: Receiver<datatypes::Event>,
     world_stream: Receiver<datatypes::world_builder::World>,
@@ -34,14 +60,27 @@ pub struct App {
 
 impl App {
     /// Construct a new instance of [`App`].
-    pub fn new() -> Self {
+    pub fn new(source: Source, chart_window: Duration, layout: PanelLayout, quote: String) -> Self {
+        let playback = matches!(source, Source::Replay(_)).then(|| Arc::new(Mutex::new(Playback::new(1.0))));
         Self {
             running: true,
-            partials: Vec::new(),
-            fulls: Vec::new(),
-            orders: Vec::new(),
-            fills: Vec::new(),
+            source,
+            partials: EventList::new("Partial Books", Self::describe_partial),
+            fulls: EventList::new("Full Books", Self::describe_event),
+            events: EventList::new("Events", Self::describe_event),
             worlds: Vec::new(),
+            ladder: Ladder::default(),
+            blotter: Blotter::default(),
+            health: RecorderHealth::default(),
+            portfolio: Portfolio::new(quote),
+            layout,
+            areas: Vec::new(),
+            detail: None,
+            charts: Charts::new(chart_window.as_millis() as u64),
+            overview: Overview::default(),
+            playback,
+            jump: None,
+            follower: None,
             event_stream: channel().1,
             world_stream: channel().1,
         }
@@ -50,64 +89,134 @@ impl App {
     /// Run the application's main loop.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         terminal.clear().unwrap();
+        self.start()?;
 
-        // Flatten the new_stream logic for "fulls"
-        {
-            let (tx, rx) = channel();
-            self.event_stream = rx;
-
-            thread::spawn(move || {
-                let command = "tail -fq data/*";
-                for line in Self::launch_command(command) {
-                    let line: datatypes::Event = serde_json::from_str(&line).unwrap();
-
-                    tx.send(line).ok();
-                }
-            });
+        self.running = true;
+        while self.running {
+            terminal.draw(|frame| self.draw(frame))?;
+            self.handle_crossterm_events()?;
         }
+        Ok(())
+    }
 
-        // Flatten the new_stream logic for "world"
-        {
-            let (tx, rx) = channel();
-            self.world_stream = rx;
+    // one reader parses the stream, the panels get every event and the
+    // world is built from the same events as they go past
+    fn start(&mut self) -> Result<()> {
+        let (event_tx, event_rx) = channel();
+        let (world_tx, world_rx) = channel();
+        self.event_stream = event_rx;
+        self.world_stream = world_rx;
+
+        match (&self.source, &self.playback) {
+            (Source::Replay(paths), Some(playback)) => {
+                let events = Interleaver::open(paths, OrderBy::Receive, false)?;
+                let player = Player::new(events, playback.clone());
+                thread::spawn(move || Self::read_events(player, event_tx, world_tx));
+            }
+            (source, _) => {
+                let (reader, follower) = source.open()?;
+                self.follower = follower;
+                thread::spawn(move || Self::read_events(Self::parse_lines(reader), event_tx, world_tx));
+            }
+        }
+        Ok(())
+    }
 
-            thread::spawn(move || {
-                let command = "tail -fq data/*";
+    fn parse_lines(reader: Box<dyn BufRead + Send>) -> impl Iterator<Item = datatypes::Event> {
+        reader
+            .lines()
+            .map_while(|line| line.ok())
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str::<datatypes::Event>(&line).ok())
+    }
 
-                let world_builder = datatypes::world_builder::WorldBuilder::new(Box::new(
-                    Self::launch_command(command).map(|line| {
-                        let event: datatypes::Event = serde_json::from_str(&line).unwrap();
-                        Ok(event)
-                    }),
-                ));
+    fn read_events(
+        events: impl Iterator<Item = datatypes::Event> + 'static,
+        event_tx: Sender<datatypes::Event>,
+        world_tx: Sender<World>,
+    ) {
+        let events = events
+            .inspect(move |event| {
+                event_tx.send(event.clone()).ok();
+            })
+            .map(Ok);
+
+        let world_builder = datatypes::world_builder::WorldBuilder::new(Box::new(events));
+        for world in world_builder {
+            let Ok(world) = world else {
+                break;
+            };
+            if world_tx.send(world).is_err() {
+                break;
+            }
+        }
+    }
 
-                for world in world_builder {
-                    tx.send(world.unwrap()).ok();
-                }
-            });
+    /// Move the replay to `time`. Forwards it plays through to it, backwards
+    /// everything is cleared and the recording played again from the start.
+    fn seek(&mut self, time: u64) -> Result<()> {
+        let Some(playback) = &self.playback else {
+            return Ok(());
+        };
+
+        let mut current = playback.lock().unwrap();
+        if current.cursor.is_none_or(|cursor| time >= cursor) {
+            current.seek(time);
+            return Ok(());
         }
 
-        self.running = true;
-        while self.running {
-            terminal.draw(|frame| self.draw(frame))?;
-            self.handle_crossterm_events()?;
+        let mut restarted = Playback::new(current.speed());
+        if current.paused() {
+            restarted.toggle_pause();
         }
-        Ok(())
+        restarted.seek(time);
+        drop(current);
+        self.playback = Some(Arc::new(Mutex::new(restarted)));
+
+        self.partials.clear();
+        self.fulls.clear();
+        self.events.clear();
+        self.blotter.clear();
+        self.health.clear();
+        self.portfolio.clear();
+        self.worlds.clear();
+        self.ladder.clear();
+        self.overview.clear();
+        self.charts.clear();
+
+        self.start()
     }
 
-    fn launch_command(command: &str) -> impl Iterator<Item = String> {
-        let child = std::process::Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .stdout(std::process::Stdio::piped())
-            .spawn()
-            .unwrap();
+    fn playback_status(&self) -> Option<String> {
+        let playback = self.playback.as_ref()?.lock().unwrap();
+
+        let state = if playback.finished {
+            "finished".to_string()
+        } else if let Some(target) = playback.seeking_to() {
+            format!("seeking to {}", Self::timestamp_to_string(target))
+        } else if playback.paused() {
+            "paused".to_string()
+        } else {
+            format!("playing {}x", playback.speed())
+        };
+        let cursor = playback
+            .cursor
+            .map(|cursor| {
+                chrono::DateTime::from_timestamp_millis(cursor as i64)
+                    .unwrap()
+                    .format("%Y-%m-%d %H:%M:%S%.3f")
+                    .to_string()
+            })
+            .unwrap_or("-".to_string());
 
-        let reader = BufReader::new(child.stdout.unwrap());
-        reader.lines().map(|line| line.unwrap())
+        let keys = match &self.jump {
+            Some(input) => format!("jump to: {}_  (enter to jump, esc to cancel)", input),
+            None => "space pause, . step, +/- speed, [/] 10s back/forward, g jump".to_string(),
+        };
+        Some(format!(" {} | {} | {}", cursor, state, keys))
     }
 
-    fn timestamp_to_string(timestamp: u64) -> String {
+    pub(crate) fn timestamp_to_string(timestamp: u64) -> String {
         let receive_time = (timestamp / 1000) as i64;
         let nanos = ((timestamp % 1000) * 1000000) as u32;
         chrono::DateTime::from_timestamp(receive_time, nanos)
@@ -123,6 +232,10 @@ impl App {
     /// - <https://github.com/ratatui/ratatui/tree/master/examples>
     fn draw(&mut self, frame: &mut Frame) {
         for event in self.event_stream.try_iter() {
+            self.overview.on_event(&event);
+            self.blotter.on_event(&event);
+            self.health.on_event(&event);
+            self.events.push(event.clone());
             match event.event {
                 EventType::PartialOrderBook(_) => {
                     self.partials.push(event);
@@ -130,184 +243,151 @@ impl App {
                 EventType::FullOrderBook(_) => {
                     self.fulls.push(event);
                 }
-                EventType::OrderTradeEvent(_) => {
-                    self.orders.push(event);
-                }
+                EventType::OrderTradeEvent(_) => {}
                 EventType::AccountInformation(_) => {}
                 EventType::OpenOrders(_) => {}
+                EventType::ClockOffset(_) => {}
+                EventType::Trade(_) => {}
+                EventType::BookTicker(_) => {}
+                EventType::BookCheck(_) => {}
+                EventType::MarkPrice(_) => {}
+                EventType::FundingRate(_) => {}
+                EventType::Liquidation(_) => {}
+                EventType::Kline(_) => {}
+                EventType::SymbolInfo(_) => {}
+                EventType::AccountPosition(_) => {}
+                EventType::BalanceUpdate(_) => {}
+                EventType::StreamGap(_) => {}
+                EventType::RecorderStats(_) => {}
             }
         }
 
-        // cut streams down to 100 elements
-        for (_, stream) in &mut [
-            ("partials", &mut self.partials),
-            ("fulls", &mut self.fulls),
-            ("open_orders", &mut self.orders),
-            ("fills", &mut self.fills),
-        ] {
-            if stream.len() > 100 {
-                stream.drain(0..stream.len() - 100);
-            }
+        // only the newest world is ever shown, but every one of them is charted
+        let newest = self.world_stream.try_iter().inspect(|world| self.charts.update(world)).last();
+        if let Some(world) = newest {
+            self.ladder.update(&world);
+            self.portfolio.update(&world);
+            self.worlds.push(world);
         }
 
-        let master_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Length(3), Constraint::Fill(1)])
-            .split(frame.area());
-
-        let main_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Fill(1), Constraint::Fill(3)])
-            .split(master_layout[1]);
+        if self.worlds.len() > 1 {
+            self.worlds.drain(0..self.worlds.len() - 1);
+        }
 
-        let main_right = Layout::default()
+        let status = self.playback_status();
+        let status_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Fill(1), Constraint::Fill(1)])
-            .split(main_layout[1]);
-
-        let main_right_top = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
-            .split(main_right[0]);
-
-        let main_right_bottom = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
-            .split(main_right[1]);
-
-        let header_area = master_layout[0];
-        let world_area = main_layout[0];
-
-        let orders_area = main_right_top[0];
-        let fills_area = main_right_top[1];
-
-        let fulls_area = main_right_bottom[0];
-        let partials_area = main_right_bottom[1];
-
-        frame.render_widget(
-            Paragraph::new("top")
-                .block(Block::bordered().title("top"))
-                .centered(),
-            header_area,
-        );
-
-        // if world stream has some length, destructure it to get the last element
-        let world = self
-            .worlds
-            .last()
-            .map(|world| {
-                world
-                    .account_information
-                    .clone()
-                    .map(|account| {
-                        account
-                            .balances
-                            .into_iter()
-                            .map(|balance| format!("{}: {}", balance.asset, balance.free))
-                            .collect::<Vec<_>>()
-                            .join("\n")
-                    })
-                    .unwrap_or("No balances in world".to_string())
-            })
-            .unwrap_or("No world yet".to_string());
-
-        frame.render_widget(
-            Paragraph::new(world).block(Block::bordered().title("World")),
-            world_area,
-        );
-
-        let mut opened = vec![];
-        let mut filled = vec![];
-
-        let orders = self.orders.iter().for_each(|event| {
-            if let EventType::OrderTradeEvent(order_event) = &event.event {
-                let exec = order_event.execution_type.clone();
+            .constraints(vec![Constraint::Length(status.is_some() as u16), Constraint::Fill(1)])
+            .split(frame.area());
+        if let Some(status) = status {
+            frame.render_widget(Paragraph::new(status).reversed(), status_layout[0]);
+        }
 
-                if exec == "NEW" {
-                    opened.push(format!("{} | {}", Self::timestamp_to_string(event.receive_time), event.symbol));
-                } else {
-                    filled.push(format!("{} | {}", Self::timestamp_to_string(event.receive_time), event.symbol));
-                }
+        let body = status_layout[1];
+        let overview_height = self.overview.height().min(body.height / 3);
+        let auto = |panel| (panel == Panel::Overview).then_some(overview_height);
+
+        // ages in a replay are measured against the playback, not the wall clock
+        let now = match &self.playback {
+            Some(playback) => playback.lock().unwrap().now().unwrap_or(0),
+            None => datatypes::now_ns() / 1_000_000,
+        };
+
+        let focused = self.layout.focused();
+        self.areas = self.layout.areas(body, &auto);
+        for &(panel, area) in &self.areas {
+            self.render_panel(frame, panel, area, now);
+
+            // every panel draws its own block, so only the sides and bottom
+            // are drawn over to leave its title and corners alone
+            if Some(panel) == focused {
+                let below_title = Rect {
+                    y: area.y + 1,
+                    height: area.height.saturating_sub(1),
+                    ..area
+                };
+                frame.render_widget(
+                    Block::new()
+                        .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
+                        .border_style(Style::default().fg(Color::Yellow)),
+                    below_title,
+                );
             }
-        });
-
-        let opened_offset = opened
-            .len()
-            .saturating_sub(orders_area.height as usize - 2);
-
-        frame.render_widget(
-            Paragraph::new(opened.join("\n"))
-                .block(Block::bordered().title("Orders"))
-                .scroll((opened_offset as u16, 0)),
-            orders_area,
-        );
+        }
 
-        let fills_offset = filled
-            .len()
-            .saturating_sub(fills_area.height as usize - 2);
+        self.render_detail(frame);
+    }
 
+    fn render_detail(&self, frame: &mut Frame) {
+        let Some((json, scroll)) = &self.detail else {
+            return;
+        };
+
+        let area = frame.area();
+        let popup = Rect {
+            x: area.x + area.width / 10,
+            y: area.y + area.height / 10,
+            width: area.width - area.width / 5,
+            height: area.height - area.height / 5,
+        };
+        frame.render_widget(Clear, popup);
         frame.render_widget(
-            Paragraph::new(filled.join("\n"))
-                .block(Block::bordered().title("Fills"))
-                .scroll((fills_offset as u16, 0)),
-            fills_area,
+            Paragraph::new(json.as_str())
+                .scroll((*scroll, 0))
+                .block(Block::bordered().title("Event (↑/↓ to scroll, esc to close)")),
+            popup,
         );
+    }
 
-        // map all of fulls to FullOrderBook vector
-        let fulls = self
-            .fulls
-            .iter()
-            .filter_map(|event| {
-                let time = Self::timestamp_to_string(event.receive_time);
-                Some(format!("{} | {}", time, event.symbol))
-            })
-            .collect::<Vec<_>>();
-
-        let fulls_offset = self
-            .fulls
-            .len()
-            .saturating_sub(fulls_area.height as usize - 2);
+    fn render_panel(&self, frame: &mut Frame, panel: Panel, area: Rect, now: u64) {
+        match panel {
+            Panel::Overview => self.overview.render(frame, area, self.worlds.last(), now),
+            Panel::World => self.portfolio.render(frame, area),
+            Panel::Recorder => self.health.render(frame, area, now),
+            Panel::Ladder => self.ladder.render(frame, area),
+            Panel::Charts => self.charts.render(frame, area, self.ladder.selected_symbol()),
+            Panel::Orders => self.blotter.render_orders(frame, area),
+            Panel::Fills => self.blotter.render_fills(frame, area),
+            Panel::FullBooks => self.fulls.render(frame, area),
+            Panel::PartialBooks => self.partials.render(frame, area),
+            Panel::Events => self.events.render(frame, area),
+        }
+    }
 
-        frame.render_widget(
-            Paragraph::new(fulls.join("\n"))
-                .scroll((fulls_offset as u16, 0))
-                .block(Block::bordered().title("Full Books")),
-            fulls_area,
-        );
+    fn describe_event(event: &datatypes::Event) -> String {
+        format!(
+            "{} | {:<10} | {}",
+            Self::timestamp_to_string(event.receive_time),
+            event.symbol,
+            event.event.name()
+        )
+    }
 
-        let partials = self
-            .partials
-            .iter()
-            .filter_map(|event| {
-                let time = Self::timestamp_to_string(event.receive_time);
+    fn describe_partial(event: &datatypes::Event) -> String {
+        let time = Self::timestamp_to_string(event.receive_time);
 
-                let partial = if let EventType::PartialOrderBook(ob) = &event.event {
-                    ob
-                } else {
-                    panic!("Expected PartialOrderBook event")
-                };
+        let partial = if let EventType::PartialOrderBook(ob) = &event.event {
+            ob
+        } else {
+            panic!("Expected PartialOrderBook event")
+        };
 
-                let bids_len = partial.bids.len();
-                let asks_len = partial.asks.len();
+        let bids_len = partial.bids.len();
+        let asks_len = partial.asks.len();
 
-                Some(format!(
-                    "{} | {:<10} | Bids: {:>4} | Asks: {:>4}",
-                    time, event.symbol, bids_len, asks_len
-                ))
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
+        format!(
+            "{} | {:<10} | Bids: {:>4} | Asks: {:>4}",
+            time, event.symbol, bids_len, asks_len
+        )
+    }
 
-        let partials_offset = self
-            .partials
-            .len()
-            .saturating_sub(partials_area.height as usize - 2);
-        frame.render_widget(
-            Paragraph::new(partials)
-                .scroll((partials_offset as u16, 0))
-                .block(Block::bordered().title("Partial Books")),
-            partials_area,
-        );
+    fn event_list(&mut self, panel: Panel) -> Option<&mut EventList> {
+        match panel {
+            Panel::FullBooks => Some(&mut self.fulls),
+            Panel::PartialBooks => Some(&mut self.partials),
+            Panel::Events => Some(&mut self.events),
+            _ => None,
+        }
     }
 
     /// Reads the crossterm events and updates the state of [`App`].
@@ -318,8 +398,8 @@ impl App {
         if event::poll(Duration::from_millis(10))? {
             match event::read()? {
                 // it's important to check KeyEventKind::Press to avoid handling key release events
-                Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
-                Event::Mouse(_) => {}
+                Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key)?,
+                Event::Mouse(mouse) => self.on_mouse_event(mouse),
                 Event::Resize(_, _) => {}
                 _ => {}
             }
@@ -328,14 +408,132 @@ impl App {
         Ok(())
     }
 
+    /// Clicking a panel focuses it, the wheel scrolls the event list under it.
+    fn on_mouse_event(&mut self, mouse: MouseEvent) {
+        let under = self
+            .areas
+            .iter()
+            .find(|(_, area)| area.contains((mouse.column, mouse.row).into()))
+            .map(|(panel, _)| *panel);
+        let Some(panel) = under else {
+            return;
+        };
+
+        match mouse.kind {
+            MouseEventKind::Down(_) => self.layout.focus(panel),
+            MouseEventKind::ScrollUp => {
+                if let Some(list) = self.event_list(panel) {
+                    list.scroll(-3);
+                }
+            }
+            MouseEventKind::ScrollDown => {
+                if let Some(list) = self.event_list(panel) {
+                    list.scroll(3);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Handles the key events and updates the state of [`App`].
-    fn on_key_event(&mut self, key: KeyEvent) {
+    fn on_key_event(&mut self, key: KeyEvent) -> Result<()> {
+        let cursor = self.playback_cursor().unwrap_or(0);
+
+        if let Some((_, scroll)) = &mut self.detail {
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => *scroll += 1,
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(20),
+                KeyCode::PageDown => *scroll += 20,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.detail = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // the jump prompt takes typing ahead of the focused list, like the popup
+        if let Some(input) = &mut self.jump {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let time = player::parse_time(input, cursor);
+                    self.jump = None;
+                    if let Some(time) = time {
+                        self.seek(time)?;
+                    }
+                }
+                KeyCode::Esc => self.jump = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        let focused = self.layout.focused();
+        if let Some(list) = focused.and_then(|panel| self.event_list(panel)) {
+            if list.editing() {
+                list.on_filter_key(key);
+                return Ok(());
+            }
+            if key.code == KeyCode::Enter {
+                self.detail = list
+                    .selected()
+                    .map(|event| (serde_json::to_string_pretty(event).unwrap(), 0));
+                return Ok(());
+            }
+            if list.on_key(key) {
+                return Ok(());
+            }
+        }
+
+        if let Some(playback) = &self.playback {
+            let mut playback = playback.lock().unwrap();
+            let speed = playback.speed();
+            match key.code {
+                KeyCode::Char(' ') => playback.toggle_pause(),
+                KeyCode::Char('.') => playback.step(),
+                KeyCode::Char('+') => playback.set_speed(speed * 2.0),
+                KeyCode::Char('-') => playback.set_speed(speed / 2.0),
+                KeyCode::Char('g') => self.jump = Some(String::new()),
+                KeyCode::Char('[') => {
+                    drop(playback);
+                    return self.seek(cursor.saturating_sub(10_000));
+                }
+                KeyCode::Char(']') => {
+                    drop(playback);
+                    return self.seek(cursor + 10_000);
+                }
+                _ => {}
+            }
+        }
+
         match (key.modifiers, key.code) {
             (_, KeyCode::Esc | KeyCode::Char('q'))
             | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+            (_, KeyCode::Tab) => self.layout.focus_next(),
+            (_, KeyCode::BackTab) => self.layout.focus_previous(),
+            (_, KeyCode::Char('z')) => self.layout.toggle_maximized(),
+            (_, KeyCode::Char(c @ '0'..='9')) => {
+                // 0 is the tenth
+                let index = (c as usize - '0' as usize + 9) % 10;
+                if let Some(panel) = Panel::ALL.get(index) {
+                    self.layout.toggle(*panel);
+                }
+            }
+            (_, KeyCode::Char('s')) => self.overview.cycle_sort(),
+            (_, KeyCode::Char('r')) => self.overview.reverse_sort(),
+            (_, KeyCode::Right | KeyCode::Char('l')) => self.ladder.next_symbol(),
+            (_, KeyCode::Left | KeyCode::Char('h')) => self.ladder.previous_symbol(),
             // Add other key handlers here.
             _ => {}
         }
+        Ok(())
+    }
+
+    fn playback_cursor(&self) -> Option<u64> {
+        self.playback.as_ref()?.lock().unwrap().cursor
     }
 
     /// Set running to false to quit the application.
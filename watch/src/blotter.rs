@@ -0,0 +1,267 @@
+use std::collections::{HashMap, VecDeque};
+
+use binance::model::Order;
+use datatypes::{Event, EventType, ExecutionReport};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Row, Table},
+};
+
+// finished orders beyond this many are forgotten, oldest first
+const MAX_ORDERS: usize = 500;
+const MAX_FILLS: usize = 500;
+
+#[derive(Debug, Clone)]
+struct OrderRow {
+    symbol: String,
+    side: String,
+    order_type: String,
+    status: String,
+    price: f64,
+    qty: f64,
+    filled: f64,
+    // quantity times price of the fills known, and the quantity they add up
+    // to: short of `filled` when fills happened before the order was seen
+    filled_quote: f64,
+    priced_qty: f64,
+    updated: u64,
+}
+
+impl OrderRow {
+    fn is_open(&self) -> bool {
+        matches!(self.status.as_str(), "NEW" | "PARTIALLY_FILLED" | "PENDING_NEW")
+    }
+
+    // None until every fill of the order is known
+    fn average_price(&self) -> Option<f64> {
+        let complete = (self.filled - self.priced_qty).abs() <= self.filled * 1e-9;
+        (self.filled > 0.0 && complete).then(|| self.filled_quote / self.filled)
+    }
+
+    fn color(&self) -> Color {
+        match self.status.as_str() {
+            "FILLED" => Color::Green,
+            "PARTIALLY_FILLED" => Color::Yellow,
+            "REJECTED" => Color::Red,
+            "CANCELED" | "EXPIRED" | "EXPIRED_IN_MATCH" => Color::DarkGray,
+            _ => Color::White,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Fill {
+    time: u64,
+    symbol: String,
+    order_id: u64,
+    side: String,
+    price: f64,
+    qty: f64,
+    fee: f64,
+    // missing from tapes recorded before it was kept
+    fee_asset: Option<String>,
+    maker: bool,
+}
+
+fn parse(value: &str) -> f64 {
+    value.parse().unwrap_or(0.0)
+}
+
+/// Every order seen, as of its last execution report or open orders
+/// snapshot, and the trades that filled them.
+#[derive(Debug, Default)]
+pub struct Blotter {
+    // order ids are only unique within a symbol
+    orders: HashMap<(String, u64), OrderRow>,
+    fills: VecDeque<Fill>,
+}
+
+impl Blotter {
+    pub fn on_event(&mut self, event: &Event) {
+        match &event.event {
+            EventType::OrderTradeEvent(report) => self.on_report(report),
+            EventType::OpenOrders(orders) => orders.iter().for_each(|order| self.on_snapshot(order)),
+            _ => {}
+        }
+    }
+
+    fn on_report(&mut self, update: &ExecutionReport) {
+        let report = &update.report;
+        let order = self.orders.entry((report.symbol.clone(), report.order_id)).or_insert_with(|| OrderRow {
+            symbol: report.symbol.clone(),
+            side: report.side.clone(),
+            order_type: report.order_type.clone(),
+            status: String::new(),
+            price: parse(&report.price),
+            qty: parse(&report.qty),
+            filled: 0.0,
+            filled_quote: 0.0,
+            priced_qty: 0.0,
+            updated: 0,
+        });
+
+        // reports can arrive behind a snapshot that already has them
+        if report.event_time < order.updated {
+            return;
+        }
+        order.status = report.order_status.clone();
+        order.filled = parse(&report.accumulated_qty_filled_trades);
+        order.updated = report.event_time;
+
+        if report.execution_type == "TRADE" {
+            let price = parse(&report.price_last_filled_trade);
+            let qty = parse(&report.qty_last_filled_trade);
+            order.filled_quote += price * qty;
+            order.priced_qty += qty;
+
+            self.fills.push_back(Fill {
+                time: report.trade_order_time,
+                symbol: report.symbol.clone(),
+                order_id: report.order_id,
+                side: report.side.clone(),
+                price,
+                qty,
+                fee: parse(&report.commission),
+                fee_asset: update.commission_asset.clone(),
+                maker: report.is_buyer_maker,
+            });
+            if self.fills.len() > MAX_FILLS {
+                self.fills.pop_front();
+            }
+        }
+
+        self.forget_finished();
+    }
+
+    fn on_snapshot(&mut self, snapshot: &Order) {
+        let order = self.orders.entry((snapshot.symbol.clone(), snapshot.order_id)).or_insert_with(|| OrderRow {
+            symbol: snapshot.symbol.clone(),
+            side: snapshot.side.clone(),
+            order_type: snapshot.type_name.clone(),
+            status: String::new(),
+            price: snapshot.price,
+            qty: parse(&snapshot.orig_qty),
+            filled: 0.0,
+            filled_quote: 0.0,
+            priced_qty: 0.0,
+            updated: 0,
+        });
+
+        if snapshot.update_time < order.updated {
+            return;
+        }
+        order.status = snapshot.status.clone();
+        order.filled = parse(&snapshot.executed_qty);
+        // the snapshot covers every fill so far
+        order.filled_quote = parse(&snapshot.cummulative_quote_qty);
+        order.priced_qty = order.filled;
+        order.updated = snapshot.update_time;
+    }
+
+    fn forget_finished(&mut self) {
+        while self.orders.len() > MAX_ORDERS {
+            let oldest = self
+                .orders
+                .iter()
+                .filter(|(_, order)| !order.is_open())
+                .min_by_key(|(_, order)| order.updated)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => self.orders.remove(&key),
+                None => break,
+            };
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.orders.clear();
+        self.fills.clear();
+    }
+
+    pub fn render_orders(&self, frame: &mut Frame, area: Rect) {
+        // open orders first, then the most recently updated
+        let mut orders = self.orders.iter().collect::<Vec<_>>();
+        orders.sort_by_key(|(_, order)| (!order.is_open(), std::cmp::Reverse(order.updated)));
+
+        let rows = orders.into_iter().map(|((_, id), order)| {
+            let average = match order.average_price() {
+                Some(price) => format!("{:.8}", price),
+                None if order.filled > 0.0 => "?".to_string(),
+                None => "-".to_string(),
+            };
+            Row::new(vec![
+                crate::App::timestamp_to_string(order.updated),
+                order.symbol.clone(),
+                id.to_string(),
+                order.side.clone(),
+                order.order_type.clone(),
+                order.status.clone(),
+                format!("{}", order.price),
+                format!("{}", order.filled),
+                format!("{}", (order.qty - order.filled).max(0.0)),
+                average,
+            ])
+            .style(Style::default().fg(order.color()))
+        });
+
+        let header = ["Updated", "Symbol", "Order", "Side", "Type", "Status", "Price", "Filled", "Remaining", "Avg price"];
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(4),
+            Constraint::Length(10),
+            Constraint::Length(16),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ];
+        let open = self.orders.values().filter(|order| order.is_open()).count();
+
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(Row::new(header).bold())
+                .block(Block::bordered().title(format!("Orders ({} open)", open))),
+            area,
+        );
+    }
+
+    pub fn render_fills(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.fills.iter().rev().map(|fill| {
+            let color = if fill.side == "BUY" { Color::Green } else { Color::Red };
+            Row::new(vec![
+                crate::App::timestamp_to_string(fill.time),
+                fill.symbol.clone(),
+                fill.order_id.to_string(),
+                fill.side.clone(),
+                format!("{}", fill.price),
+                format!("{}", fill.qty),
+                format!("{} {}", fill.fee, fill.fee_asset.as_deref().unwrap_or("?")),
+                if fill.maker { "maker" } else { "taker" }.to_string(),
+            ])
+            .style(Style::default().fg(color))
+        });
+
+        let header = ["Time", "Symbol", "Order", "Side", "Price", "Qty", "Fee", "Liquidity"];
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(4),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(18),
+            Constraint::Length(9),
+        ];
+
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(Row::new(header).bold())
+                .block(Block::bordered().title("Fills")),
+            area,
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::{HashMap, VecDeque};
+
+use datatypes::world_builder::World;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Chart, Dataset, GraphType},
+};
+
+// most samples kept per symbol, however long the window
+const MAX_SAMPLES: usize = 2_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    time: u64,
+    mid: f64,
+    spread_bps: f64,
+    // (bid qty - ask qty) / (bid qty + ask qty) at the top of the book, in [-1, 1]
+    imbalance: f64,
+}
+
+type Value = fn(&Sample) -> f64;
+
+/// Top of book history per symbol, over a rolling window.
+#[derive(Debug)]
+pub struct Charts {
+    window_ms: u64,
+    history: HashMap<String, VecDeque<Sample>>,
+    last_update_ids: HashMap<String, u64>,
+}
+
+impl Charts {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            history: HashMap::new(),
+            last_update_ids: HashMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, world: &World) {
+        // spread the buffer evenly over the window rather than keeping only the newest burst
+        let min_interval = self.window_ms / MAX_SAMPLES as u64;
+
+        for (symbol, ob) in &world.order_books {
+            if self.last_update_ids.insert(symbol.clone(), ob.last_update_id) == Some(ob.last_update_id) {
+                continue;
+            }
+            let (Some(bid), Some(ask)) = (ob.bids.first(), ob.asks.first()) else {
+                continue;
+            };
+
+            let history = self.history.entry(symbol.clone()).or_default();
+            if history.back().is_some_and(|last| world.time < last.time + min_interval) {
+                continue;
+            }
+
+            let mid = (bid.price + ask.price) / 2.0;
+            history.push_back(Sample {
+                time: world.time,
+                mid,
+                spread_bps: (ask.price - bid.price) / mid * 10_000.0,
+                imbalance: (bid.qty - ask.qty) / (bid.qty + ask.qty),
+            });
+
+            while history.len() > MAX_SAMPLES
+                || history.front().is_some_and(|first| first.time + self.window_ms < world.time)
+            {
+                history.pop_front();
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.last_update_ids.clear();
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, symbol: Option<&String>) {
+        let areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, 3); 3])
+            .split(area);
+
+        let history = symbol.and_then(|symbol| self.history.get(symbol));
+        let symbol = symbol.map(String::as_str).unwrap_or("-");
+
+        let series: [(&str, Value, Color); 3] = [
+            ("Mid", |sample| sample.mid, Color::Cyan),
+            ("Spread bps", |sample| sample.spread_bps, Color::Yellow),
+            ("Imbalance", |sample| sample.imbalance, Color::Magenta),
+        ];
+
+        for ((name, value, color), area) in series.into_iter().zip(areas.iter()) {
+            // seconds relative to the newest sample, so the x axis reads "-300 .. 0"
+            let newest = history.and_then(|history| history.back()).map(|sample| sample.time).unwrap_or(0);
+            let points = history
+                .map(|history| {
+                    history
+                        .iter()
+                        .map(|sample| ((sample.time as f64 - newest as f64) / 1000.0, value(sample)))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            let (low, high) = points
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), (_, y)| (low.min(*y), high.max(*y)));
+            let (low, high) = if points.is_empty() {
+                (0.0, 1.0)
+            } else if low == high {
+                // a flat line still needs a range to be drawn in
+                (low - 1.0, high + 1.0)
+            } else {
+                (low, high)
+            };
+            let window = self.window_ms as f64 / 1000.0;
+
+            let dataset = Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(&points);
+
+            let chart = Chart::new(vec![dataset])
+                .block(Block::bordered().title(format!("{} {}", name, symbol)))
+                .x_axis(
+                    Axis::default()
+                        .bounds([-window, 0.0])
+                        .labels([Span::raw(format!("-{}s", window)), Span::raw("0")]),
+                )
+                .y_axis(
+                    Axis::default()
+                        .bounds([low, high])
+                        .labels([Span::raw(format!("{:.4}", low)), Span::raw(format!("{:.4}", high))]),
+                );
+
+            frame.render_widget(chart, *area);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use datatypes::Event;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph},
+};
+
+// events kept per list, oldest dropped first
+const CAPACITY: usize = 10_000;
+const PAGE: usize = 20;
+
+/// A scrollable, filterable list of raw events.
+///
+/// It follows the newest event until one is selected, and the selection
+/// stays on the same event as new ones arrive.
+#[derive(Debug)]
+pub struct EventList {
+    title: &'static str,
+    describe: fn(&Event) -> String,
+    // with a sequence number, so a selection survives old events being dropped
+    events: VecDeque<(u64, Event)>,
+    next_sequence: u64,
+    // sequence numbers of the events the filter lets through, oldest first,
+    // kept up to date as events come and go and rebuilt when the filter changes
+    shown: VecDeque<u64>,
+    selected: Option<u64>,
+    filter: String,
+    editing: bool,
+}
+
+impl EventList {
+    pub fn new(title: &'static str, describe: fn(&Event) -> String) -> Self {
+        Self {
+            title,
+            describe,
+            events: VecDeque::new(),
+            next_sequence: 0,
+            shown: VecDeque::new(),
+            selected: None,
+            filter: String::new(),
+            editing: false,
+        }
+    }
+
+    pub fn push(&mut self, event: Event) {
+        if self.matches(&event) {
+            self.shown.push_back(self.next_sequence);
+        }
+        self.events.push_back((self.next_sequence, event));
+        self.next_sequence += 1;
+        if self.events.len() > CAPACITY {
+            let (dropped, _) = self.events.pop_front().unwrap();
+            if self.shown.front() == Some(&dropped) {
+                self.shown.pop_front();
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.shown.clear();
+        self.selected = None;
+    }
+
+    // every space separated term has to be in the symbol or the event type
+    fn matches(&self, event: &Event) -> bool {
+        let symbol = event.symbol.to_lowercase();
+        let name = event.event.name().to_lowercase();
+        self.filter
+            .to_lowercase()
+            .split_whitespace()
+            .all(|term| symbol.contains(term) || name.contains(term))
+    }
+
+    fn refilter(&mut self) {
+        self.shown = self
+            .events
+            .iter()
+            .filter(|(_, event)| self.matches(event))
+            .map(|(sequence, _)| *sequence)
+            .collect();
+    }
+
+    // the events kept have consecutive sequence numbers
+    fn event(&self, sequence: u64) -> Option<&Event> {
+        let first = self.events.front()?.0;
+        let index = sequence.checked_sub(first)?;
+        self.events.get(index as usize).map(|(_, event)| event)
+    }
+
+    // where the selection is among the shown events
+    fn selected_position(&self) -> Option<usize> {
+        self.shown.binary_search(&self.selected?).ok()
+    }
+
+    /// The selected event, if it's still kept.
+    pub fn selected(&self) -> Option<&Event> {
+        self.event(self.selected?)
+    }
+
+    /// Move the selection by `by` events, up is negative. Moving past the
+    /// newest event goes back to following.
+    pub fn scroll(&mut self, by: isize) {
+        if self.shown.is_empty() {
+            return;
+        }
+        let last = self.shown.len() - 1;
+        let position = self.selected_position().unwrap_or(last);
+
+        let position = position as isize + by;
+        self.selected = if position > last as isize && self.selected.is_some() {
+            None
+        } else {
+            Some(self.shown[position.clamp(0, last as isize) as usize])
+        };
+    }
+
+    pub fn toggle_follow(&mut self) {
+        if self.selected.is_some() {
+            self.selected = None;
+        } else {
+            self.scroll(0);
+        }
+    }
+
+    pub fn editing(&self) -> bool {
+        self.editing
+    }
+
+    pub fn start_filter(&mut self) {
+        self.editing = true;
+    }
+
+    /// Type into the filter, which applies as it's typed.
+    pub fn on_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) => self.filter.push(c),
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Enter => {
+                self.editing = false;
+                return;
+            }
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.editing = false;
+            }
+            _ => return,
+        }
+        self.refilter();
+    }
+
+    /// Keys for moving around the list, false if it isn't one of them.
+    pub fn on_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+            KeyCode::PageUp => self.scroll(-(PAGE as isize)),
+            KeyCode::PageDown => self.scroll(PAGE as isize),
+            KeyCode::Home => self.scroll(isize::MIN / 2),
+            KeyCode::End => self.selected = None,
+            KeyCode::Char('f') => self.toggle_follow(),
+            KeyCode::Char('/') => self.start_filter(),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let shown = self.shown.len();
+        let height = (area.height as usize).saturating_sub(2);
+
+        let selected = self.selected_position();
+        // the newest events when following, otherwise the selection in the middle
+        let offset = match selected {
+            Some(position) => position.saturating_sub(height / 2).min(shown.saturating_sub(height)),
+            None => shown.saturating_sub(height),
+        };
+
+        let lines = self
+            .shown
+            .range(offset..(offset + height).min(shown))
+            .enumerate()
+            .filter_map(|(row, sequence)| Some((offset + row, self.event(*sequence)?)))
+            .map(|(position, event)| {
+                let line = Line::from((self.describe)(event));
+                if Some(position) == selected {
+                    line.style(Style::default().fg(Color::Black).bg(Color::Cyan))
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut title = format!("{} ({}/{})", self.title, shown, self.events.len());
+        if self.editing {
+            title.push_str(&format!(" filter: {}_", self.filter));
+        } else if !self.filter.is_empty() {
+            title.push_str(&format!(" filter: {}", self.filter));
+        }
+        if self.selected.is_some() {
+            title.push_str(" [paused, f to follow]");
+        }
+
+        let title = if self.editing { Line::from(title).yellow() } else { Line::from(title) };
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+    }
+}
@@ -0,0 +1,179 @@
+use datatypes::recorder_stats::LATENCY_BUCKETS;
+use datatypes::{Event, EventType, RecorderStats};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+};
+
+// stats missing for this many intervals means the recorder is down or stuck
+const STALE_INTERVALS: u64 = 3;
+const LATENCY_WARN_MS: f64 = 100.0;
+const LATENCY_ALERT_MS: f64 = 500.0;
+// share of the request weight limit in use
+const WEIGHT_WARN: f64 = 0.8;
+const HISTOGRAM_WIDTH: u64 = 20;
+
+fn level(alert: bool, warn: bool) -> Style {
+    if alert {
+        Style::default().fg(Color::Red).bold()
+    } else if warn {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().fg(Color::Green)
+    }
+}
+
+fn bytes(n: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = n as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < units.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, units[unit])
+}
+
+fn uptime(ms: u64) -> String {
+    let s = ms / 1000;
+    format!("{}h{:02}m{:02}s", s / 3600, s / 60 % 60, s % 60)
+}
+
+fn milliseconds(ms: f64) -> String {
+    if ms.is_infinite() {
+        format!(">{}ms", LATENCY_BUCKETS[LATENCY_BUCKETS.len() - 1])
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+/// How `record` is doing, from the stats it writes to the tape.
+#[derive(Debug, Default)]
+pub struct RecorderHealth {
+    // receive time of the latest stats and the stats
+    latest: Option<(u64, RecorderStats)>,
+    // the stats before from the same run, to tell what changed over the last interval
+    previous: Option<RecorderStats>,
+}
+
+// the totals a recorder counts up from its start
+const TOTALS: [fn(&RecorderStats) -> u64; 6] = [
+    |s| s.bytes_written,
+    |s| s.n_gaps,
+    |s| s.n_reconnects,
+    |s| s.n_rest_errors,
+    |s| s.n_rate_limited,
+    |s| s.n_banned,
+];
+
+// a restarted recorder (or another one writing to the same tape) starts its
+// totals from scratch, so they can't be compared
+fn same_run(before: &RecorderStats, after: &RecorderStats) -> bool {
+    before.start_time == after.start_time && TOTALS.iter().all(|total| total(before) <= total(after))
+}
+
+impl RecorderHealth {
+    pub fn on_event(&mut self, event: &Event) {
+        if let EventType::RecorderStats(stats) = &event.event {
+            self.previous = self
+                .latest
+                .take()
+                .map(|(_, previous)| previous)
+                .filter(|previous| same_run(previous, stats));
+            self.latest = Some((event.receive_time, stats.clone()));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.latest = None;
+        self.previous = None;
+    }
+
+    /// `now` is in milliseconds, the age of the stats is measured against it.
+    pub fn render(&self, frame: &mut Frame, area: Rect, now: u64) {
+        let Some((received, stats)) = &self.latest else {
+            frame.render_widget(
+                Paragraph::new("No recorder stats yet").block(Block::bordered().title("Recorder")),
+                area,
+            );
+            return;
+        };
+
+        // running totals only matter when they went up, which isn't known
+        // until there are two stats from the same run
+        let delta = |total: fn(&RecorderStats) -> u64| {
+            self.previous
+                .as_ref()
+                .map_or(0, |previous| total(stats).saturating_sub(total(previous)))
+        };
+        let age = now.saturating_sub(*received);
+        let stale = age > STALE_INTERVALS * stats.interval_ms.max(1);
+        let interval_s = stats.interval_ms.max(1) as f64 / 1000.0;
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::raw(format!("up {}, ", uptime(received.saturating_sub(stats.start_time)))),
+                Span::styled(format!("stats {:.1}s old", age as f64 / 1000.0), level(stale, false)),
+            ]),
+            Line::from(format!("written {}", bytes(stats.bytes_written))),
+            Line::from(vec![
+                Span::styled(format!("reconnects {} ", stats.n_reconnects), level(false, delta(|s| s.n_reconnects) > 0)),
+                Span::styled(format!("gaps {}", stats.n_gaps), level(false, delta(|s| s.n_gaps) > 0)),
+            ]),
+            Line::from(vec![
+                Span::styled(format!("REST errors {} ", stats.n_rest_errors), level(delta(|s| s.n_rest_errors) > 0, false)),
+                Span::styled(format!("429s {} ", stats.n_rate_limited), level(delta(|s| s.n_rate_limited) > 0, false)),
+                Span::styled(format!("418s {}", stats.n_banned), level(delta(|s| s.n_banned) > 0, false)),
+            ]),
+            Line::styled(
+                format!("weight {}/{}", stats.used_weight, stats.weight_limit),
+                level(false, stats.used_weight as f64 > WEIGHT_WARN * stats.weight_limit as f64),
+            ),
+        ];
+
+        // latency
+        let p50 = stats.latency.quantile(0.5);
+        let p99 = stats.latency.quantile(0.99);
+        if let (Some(p50), Some(p99)) = (p50, p99) {
+            lines.push(Line::styled(
+                format!("latency p50 {} p99 {}", milliseconds(p50), milliseconds(p99)),
+                level(p99 > LATENCY_ALERT_MS, p99 > LATENCY_WARN_MS),
+            ));
+        }
+        let most = stats.latency.counts.iter().copied().max().unwrap_or(0).max(1);
+        for (bucket, count) in stats.latency.counts.iter().enumerate() {
+            let bound = LATENCY_BUCKETS.get(bucket).copied().unwrap_or(f64::INFINITY);
+            let bar = "█".repeat((count * HISTOGRAM_WIDTH / most) as usize);
+            lines.push(Line::from(format!("{:>8} {:<20} {}", milliseconds(bound), bar, count)));
+        }
+
+        // per symbol rates over the last interval, a symbol that went quiet is an alert
+        lines.push(Line::from("symbol      msg/s  fb/min").bold());
+        for (symbol, messages) in &stats.messages {
+            let full_books = stats.full_books.get(symbol).copied().unwrap_or(0);
+            lines.push(Line::styled(
+                format!(
+                    "{:<10} {:>6.1} {:>7.1}",
+                    symbol,
+                    *messages as f64 / interval_s,
+                    full_books as f64 * 60.0 / interval_s
+                ),
+                level(*messages == 0, false),
+            ));
+        }
+        let quiet = self
+            .previous
+            .iter()
+            .flat_map(|previous| previous.messages.keys())
+            .filter(|symbol| !stats.messages.contains_key(*symbol));
+        for symbol in quiet {
+            lines.push(Line::styled(format!("{:<10} {:>6.1}", symbol, 0.0), level(true, false)));
+        }
+
+        let title = if stale { "Recorder (stale)" } else { "Recorder" };
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+    }
+}
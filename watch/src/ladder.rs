@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use binance::model::{Asks, Bids, OrderBook};
+use datatypes::world_builder::World;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Cell, Row, Table},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Side {
+    Bid,
+    Ask,
+}
+
+// f64 prices can't be hashed, their bits can
+type LevelKey = (Side, u64);
+
+fn levels(ob: &OrderBook) -> HashMap<LevelKey, f64> {
+    let bids = ob.bids.iter().map(|Bids { price, qty }| ((Side::Bid, price.to_bits()), *qty));
+    let asks = ob.asks.iter().map(|Asks { price, qty }| ((Side::Ask, price.to_bits()), *qty));
+    bids.chain(asks).collect()
+}
+
+/// Top of book for one symbol at a time, levels that changed in the last
+/// update highlighted.
+#[derive(Debug, Default)]
+pub struct Ladder {
+    selected: usize,
+    books: BTreeMap<String, OrderBook>,
+    changed: HashMap<String, HashSet<LevelKey>>,
+}
+
+impl Ladder {
+    pub fn update(&mut self, world: &World) {
+        for (symbol, ob) in &world.order_books {
+            let previous = self.books.get(symbol);
+            if previous.is_some_and(|previous| previous.last_update_id == ob.last_update_id) {
+                continue;
+            }
+
+            let old = previous.map(levels).unwrap_or_default();
+            let changed = levels(ob)
+                .into_iter()
+                .filter(|(level, qty)| old.get(level) != Some(qty))
+                .map(|(level, _)| level)
+                .collect();
+
+            self.changed.insert(symbol.clone(), changed);
+            self.books.insert(symbol.clone(), ob.clone());
+        }
+    }
+
+    /// Forget every book, keeping the selection.
+    pub fn clear(&mut self) {
+        self.books.clear();
+        self.changed.clear();
+    }
+
+    pub fn selected_symbol(&self) -> Option<&String> {
+        self.books.keys().nth(self.selected)
+    }
+
+    pub fn next_symbol(&mut self) {
+        if !self.books.is_empty() {
+            self.selected = (self.selected + 1) % self.books.len();
+        }
+    }
+
+    pub fn previous_symbol(&mut self) {
+        if !self.books.is_empty() {
+            self.selected = (self.selected + self.books.len() - 1) % self.books.len();
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let Some(symbol) = self.selected_symbol() else {
+            frame.render_widget(Table::default().block(Block::bordered().title("Ladder (no books yet)")), area);
+            return;
+        };
+        let ob = &self.books[symbol];
+        let changed = &self.changed[symbol];
+
+        // borders, header and the spread row
+        let depth = (area.height.saturating_sub(4) / 2) as usize;
+
+        let row = |side: Side, price: f64, qty: f64, cumulative: f64| {
+            let color = match side {
+                Side::Bid => Color::Green,
+                Side::Ask => Color::Red,
+            };
+            let style = if changed.contains(&(side, price.to_bits())) {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else {
+                Style::default().fg(color)
+            };
+            Row::new(vec![
+                Cell::from(format!("{:>12}", price)),
+                Cell::from(format!("{:>12}", qty)),
+                Cell::from(format!("{:>12.4}", cumulative)),
+            ])
+            .style(style)
+        };
+
+        // asks count up from the best, shown best ask nearest the spread
+        let mut cumulative = 0.0;
+        let mut asks = ob
+            .asks
+            .iter()
+            .take(depth)
+            .map(|ask| {
+                cumulative += ask.qty;
+                row(Side::Ask, ask.price, ask.qty, cumulative)
+            })
+            .collect::<Vec<_>>();
+        asks.reverse();
+
+        let mut cumulative = 0.0;
+        let bids = ob.bids.iter().take(depth).map(|bid| {
+            cumulative += bid.qty;
+            row(Side::Bid, bid.price, bid.qty, cumulative)
+        });
+
+        let spread = match (ob.bids.first(), ob.asks.first()) {
+            (Some(bid), Some(ask)) => format!("{:>12.8}", ask.price - bid.price),
+            _ => format!("{:>12}", "-"),
+        };
+        let spread = Row::new(vec![Cell::from(spread), Cell::from(format!("{:>12}", "spread"))]).dark_gray();
+
+        let rows = asks.into_iter().chain(std::iter::once(spread)).chain(bids);
+        let title = format!(
+            "Ladder {} ({}/{}, ←/→ to switch) #{}",
+            symbol,
+            self.selected + 1,
+            self.books.len(),
+            ob.last_update_id
+        );
+
+        frame.render_widget(
+            Table::new(rows, [Constraint::Length(13), Constraint::Length(13), Constraint::Length(13)])
+                .header(Row::new(vec!["Price", "Size", "Cumulative"]).bold())
+                .block(Block::bordered().title(title)),
+            area,
+        );
+    }
+}
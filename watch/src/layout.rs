@@ -0,0 +1,261 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::Deserialize;
+
+/// Everything `watch` can show.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    Overview,
+    World,
+    Recorder,
+    Ladder,
+    Charts,
+    Orders,
+    Fills,
+    FullBooks,
+    PartialBooks,
+    Events,
+}
+
+impl Panel {
+    /// In the order of the number keys that toggle them, 0 last.
+    pub const ALL: [Panel; 10] = [
+        Panel::Overview,
+        Panel::World,
+        Panel::Recorder,
+        Panel::Ladder,
+        Panel::Charts,
+        Panel::Orders,
+        Panel::Fills,
+        Panel::FullBooks,
+        Panel::PartialBooks,
+        Panel::Events,
+    ];
+}
+
+/// How much of its split a node takes.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Size {
+    /// A share of what the fixed sizes leave
+    Fill(u16),
+    /// Rows or columns
+    Length(u16),
+    Percent(u16),
+    /// The panel's own height (only the overview has one), otherwise fill
+    Auto,
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Size::Fill(1)
+    }
+}
+
+/// A panel, or panels stacked in rows or side by side in columns, e.g.
+/// `{"rows": [{"panel": "overview", "size": "auto"}, {"columns": [...]}]}`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Node {
+    Panel {
+        panel: Panel,
+        #[serde(default)]
+        size: Size,
+    },
+    Rows {
+        rows: Vec<Node>,
+        #[serde(default)]
+        size: Size,
+    },
+    Columns {
+        columns: Vec<Node>,
+        #[serde(default)]
+        size: Size,
+    },
+}
+
+impl Node {
+    fn panel(panel: Panel, size: Size) -> Self {
+        Node::Panel { panel, size }
+    }
+
+    fn size(&self) -> Size {
+        match self {
+            Node::Panel { size, .. } | Node::Rows { size, .. } | Node::Columns { size, .. } => *size,
+        }
+    }
+
+    fn panels(&self, panels: &mut Vec<Panel>) {
+        match self {
+            Node::Panel { panel, .. } => panels.push(*panel),
+            Node::Rows { rows: children, .. } | Node::Columns { columns: children, .. } => {
+                children.iter().for_each(|child| child.panels(panels))
+            }
+        }
+    }
+
+    fn visible(&self, hidden: &HashSet<Panel>) -> bool {
+        let mut panels = Vec::new();
+        self.panels(&mut panels);
+        panels.iter().any(|panel| !hidden.contains(panel))
+    }
+
+    fn split(
+        &self,
+        area: Rect,
+        hidden: &HashSet<Panel>,
+        auto: &dyn Fn(Panel) -> Option<u16>,
+        areas: &mut Vec<(Panel, Rect)>,
+    ) {
+        let (direction, children) = match self {
+            Node::Panel { panel, .. } => {
+                areas.push((*panel, area));
+                return;
+            }
+            Node::Rows { rows, .. } => (Direction::Vertical, rows),
+            Node::Columns { columns, .. } => (Direction::Horizontal, columns),
+        };
+
+        let children = children.iter().filter(|child| child.visible(hidden)).collect::<Vec<_>>();
+        let constraints = children.iter().map(|child| match (child.size(), child) {
+            (Size::Fill(n), _) => Constraint::Fill(n),
+            (Size::Length(n), _) => Constraint::Length(n),
+            (Size::Percent(n), _) => Constraint::Percentage(n),
+            (Size::Auto, Node::Panel { panel, .. }) => auto(*panel).map_or(Constraint::Fill(1), Constraint::Length),
+            (Size::Auto, _) => Constraint::Fill(1),
+        });
+
+        let split = Layout::default().direction(direction).constraints(constraints).split(area);
+        for (child, area) in children.into_iter().zip(split.iter()) {
+            child.split(*area, hidden, auto, areas);
+        }
+    }
+}
+
+/// Which panels are shown where, plus what's been hidden, focused or
+/// maximised from the keyboard.
+#[derive(Debug)]
+pub struct PanelLayout {
+    root: Node,
+    hidden: HashSet<Panel>,
+    maximized: bool,
+    focused: usize,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        use Panel::*;
+
+        let column = |size, panels: Vec<(Panel, Size)>| Node::Columns {
+            columns: panels.into_iter().map(|(panel, size)| Node::panel(panel, size)).collect(),
+            size,
+        };
+        let fill = Size::Fill(1);
+
+        let root = Node::Rows {
+            rows: vec![
+                Node::panel(Overview, Size::Auto),
+                Node::Columns {
+                    columns: vec![
+                        Node::Rows {
+                            rows: vec![Node::panel(World, fill), Node::panel(Recorder, Size::Fill(2))],
+                            size: fill,
+                        },
+                        Node::panel(Ladder, Size::Length(45)),
+                        Node::Rows {
+                            rows: vec![
+                                Node::panel(Charts, fill),
+                                column(fill, vec![(Orders, fill), (Fills, fill)]),
+                                column(fill, vec![(FullBooks, fill), (PartialBooks, fill), (Events, fill)]),
+                            ],
+                            size: Size::Fill(3),
+                        },
+                    ],
+                    size: fill,
+                },
+            ],
+            size: fill,
+        };
+
+        Self::new(root)
+    }
+}
+
+impl PanelLayout {
+    pub fn new(root: Node) -> Self {
+        Self {
+            root,
+            hidden: HashSet::new(),
+            maximized: false,
+            focused: 0,
+        }
+    }
+
+    /// Read a layout from a JSON file, see [`Node`].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let root = serde_json::from_reader(file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?;
+        Ok(Self::new(root))
+    }
+
+    // panels that aren't hidden, in the order they appear in the layout
+    fn shown(&self) -> Vec<Panel> {
+        let mut panels = Vec::new();
+        self.root.panels(&mut panels);
+        panels.retain(|panel| !self.hidden.contains(panel));
+        panels
+    }
+
+    pub fn focused(&self) -> Option<Panel> {
+        let shown = self.shown();
+        shown.get(self.focused.min(shown.len().saturating_sub(1))).copied()
+    }
+
+    pub fn focus(&mut self, panel: Panel) {
+        if let Some(index) = self.shown().iter().position(|shown| *shown == panel) {
+            self.focused = index;
+        }
+    }
+
+    pub fn focus_next(&mut self) {
+        let n = self.shown().len().max(1);
+        self.focused = (self.focused.min(n - 1) + 1) % n;
+    }
+
+    pub fn focus_previous(&mut self) {
+        let n = self.shown().len().max(1);
+        self.focused = (self.focused.min(n - 1) + n - 1) % n;
+    }
+
+    pub fn toggle_maximized(&mut self) {
+        self.maximized = !self.maximized;
+    }
+
+    /// Hide a panel, or show it again.
+    pub fn toggle(&mut self, panel: Panel) {
+        if !self.hidden.remove(&panel) {
+            self.hidden.insert(panel);
+        }
+    }
+
+    /// Where each shown panel goes, `auto` gives the height of panels sized
+    /// [`Size::Auto`].
+    pub fn areas(&self, area: Rect, auto: &dyn Fn(Panel) -> Option<u16>) -> Vec<(Panel, Rect)> {
+        if self.maximized
+            && let Some(panel) = self.focused()
+        {
+            return vec![(panel, area)];
+        }
+
+        let mut areas = Vec::new();
+        if self.root.visible(&self.hidden) {
+            self.root.split(area, &self.hidden, auto, &mut areas);
+        }
+        areas
+    }
+}
@@ -1,11 +1,77 @@
 pub use app::App;
 
 pub mod app;
+pub mod blotter;
+pub mod charts;
+pub mod event_list;
+pub mod health;
+pub mod ladder;
+pub mod layout;
+pub mod overview;
+pub mod player;
+pub mod portfolio;
+pub mod source;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use layout::PanelLayout;
+use source::Source;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// A data directory, recorded files, tcp://host:port, unix:///path, or - for stdin (the default)
+    sources: Vec<String>,
+
+    /// Play the given data directories back from the start instead of following them
+    #[arg(long)]
+    replay: bool,
+
+    /// Seconds of history the charts show
+    #[arg(long, default_value_t = 300)]
+    chart_window: u64,
+
+    /// JSON file choosing which panels are shown and where, see the README
+    #[arg(long)]
+    layout: Option<PathBuf>,
+
+    /// Asset the portfolio is valued in
+    #[arg(long, default_value = "USDT")]
+    quote: String,
+}
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
+    let args = Args::parse();
+    let source = if args.replay {
+        Source::Replay(args.sources.clone())
+    } else {
+        Source::parse(&args.sources)?
+    };
+
+    // the terminal is where the keys come from, it can't be the event stream too
+    if matches!(source, Source::Stdin) && io::stdin().is_terminal() {
+        Args::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "nothing to watch, pipe events in or give a data directory, files or a socket",
+            )
+            .exit();
+    }
+
+    let layout = match &args.layout {
+        Some(path) => PanelLayout::load(path)?,
+        None => PanelLayout::default(),
+    };
+
     let terminal = ratatui::init();
-    let result = App::new().run(terminal);
+    crossterm::execute!(io::stdout(), EnableMouseCapture)?;
+    let result = App::new(source, Duration::from_secs(args.chart_window), layout, args.quote.to_uppercase()).run(terminal);
+    crossterm::execute!(io::stdout(), DisableMouseCapture)?;
     ratatui::restore();
     result
-}
\ No newline at end of file
+}
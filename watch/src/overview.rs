@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use datatypes::{Event, EventType, world_builder::World};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Row, Table},
+};
+
+// diffs older than this don't count towards the update rate
+const RATE_WINDOW_MS: u64 = 10_000;
+// a book that hasn't had a diff for this long is flagged
+const STALE_AFTER_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Symbol,
+    Spread,
+    Rate,
+    LastDiff,
+    Health,
+}
+
+impl SortBy {
+    const ALL: [SortBy; 5] = [SortBy::Symbol, SortBy::Spread, SortBy::Rate, SortBy::LastDiff, SortBy::Health];
+
+    fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|sort_by| *sort_by == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Health {
+    // ordered worst first so sorting by health puts problems at the top
+    Gap,
+    Diverged,
+    Crossed,
+    Stale,
+    NoBook,
+    Ok,
+}
+
+impl Health {
+    fn label(self) -> &'static str {
+        match self {
+            Health::Gap => "gap",
+            Health::Diverged => "diverged",
+            Health::Crossed => "crossed",
+            Health::Stale => "stale",
+            Health::NoBook => "no book",
+            Health::Ok => "ok",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Health::Ok => Color::Green,
+            Health::Stale | Health::NoBook => Color::Yellow,
+            _ => Color::Red,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SymbolStats {
+    diff_times: VecDeque<u64>,
+    last_diff: Option<u64>,
+    last_full: Option<u64>,
+    last_update_id: Option<u64>,
+    // a diff didn't follow on from the one before since the last snapshot
+    gap: bool,
+}
+
+impl SymbolStats {
+    // diffs per second over the window up to `now`, the times are only pruned
+    // as diffs arrive so a symbol that goes quiet still has its last window's
+    fn rate(&self, now: u64) -> f64 {
+        let recent = self
+            .diff_times
+            .iter()
+            .rev()
+            .take_while(|time| **time + RATE_WINDOW_MS >= now)
+            .count();
+        recent as f64 / (RATE_WINDOW_MS as f64 / 1000.0)
+    }
+}
+
+struct OverviewRow<'a> {
+    symbol: &'a str,
+    bid: Option<f64>,
+    ask: Option<f64>,
+    spread_bps: Option<f64>,
+    rate: f64,
+    since_diff: Option<u64>,
+    since_full: Option<u64>,
+    health: Health,
+}
+
+/// One line per symbol, built from the raw events (timing and sequencing)
+/// and the world (the book itself).
+#[derive(Debug)]
+pub struct Overview {
+    symbols: BTreeMap<String, SymbolStats>,
+    sort_by: SortBy,
+    reversed: bool,
+}
+
+impl Default for Overview {
+    fn default() -> Self {
+        Self {
+            symbols: BTreeMap::new(),
+            sort_by: SortBy::Symbol,
+            reversed: false,
+        }
+    }
+}
+
+impl Overview {
+    pub fn on_event(&mut self, event: &Event) {
+        match &event.event {
+            EventType::PartialOrderBook(diff) => {
+                let stats = self.symbols.entry(event.symbol.clone()).or_default();
+
+                // same check `record` makes, see its gap counter
+                let gap = stats.last_update_id.is_some_and(|last| match diff.previous_final_update_id {
+                    Some(previous) => previous != last,
+                    None => diff.first_update_id != last + 1,
+                });
+                stats.gap |= gap;
+                stats.last_update_id = Some(diff.final_update_id);
+
+                stats.last_diff = Some(event.receive_time);
+                stats.diff_times.push_back(event.receive_time);
+                while stats
+                    .diff_times
+                    .front()
+                    .is_some_and(|time| *time + RATE_WINDOW_MS < event.receive_time)
+                {
+                    stats.diff_times.pop_front();
+                }
+            }
+            EventType::FullOrderBook(ob) => {
+                let stats = self.symbols.entry(event.symbol.clone()).or_default();
+                stats.last_full = Some(event.receive_time);
+                stats.last_update_id = Some(ob.last_update_id);
+                stats.gap = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// Forget every symbol, keeping the sort order.
+    pub fn clear(&mut self) {
+        self.symbols.clear();
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.sort_by = self.sort_by.next();
+    }
+
+    pub fn reverse_sort(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    /// Rows needed to show every symbol, borders and header included.
+    pub fn height(&self) -> u16 {
+        self.symbols.len() as u16 + 3
+    }
+
+    fn rows<'a>(&'a self, world: Option<&World>, now: u64) -> Vec<OverviewRow<'a>> {
+        self.symbols
+            .iter()
+            .map(|(symbol, stats)| {
+                let ob = world.and_then(|world| world.order_books.get(symbol));
+                let bid = ob.and_then(|ob| ob.bids.first()).map(|bid| bid.price);
+                let ask = ob.and_then(|ob| ob.asks.first()).map(|ask| ask.price);
+                let spread_bps = bid.zip(ask).map(|(bid, ask)| (ask - bid) / ((ask + bid) / 2.0) * 10_000.0);
+                let since_diff = stats.last_diff.map(|time| now.saturating_sub(time));
+                let diverged = world
+                    .and_then(|world| world.book_health.get(symbol))
+                    .is_some_and(|health| health.last_check.diverged);
+
+                let health = if ob.is_none() {
+                    Health::NoBook
+                } else if stats.gap {
+                    Health::Gap
+                } else if diverged {
+                    Health::Diverged
+                } else if spread_bps.is_some_and(|spread| spread <= 0.0) {
+                    Health::Crossed
+                } else if since_diff.is_none_or(|since| since > STALE_AFTER_MS) {
+                    Health::Stale
+                } else {
+                    Health::Ok
+                };
+
+                OverviewRow {
+                    symbol,
+                    bid,
+                    ask,
+                    spread_bps,
+                    rate: stats.rate(now),
+                    since_diff,
+                    since_full: stats.last_full.map(|time| now.saturating_sub(time)),
+                    health,
+                }
+            })
+            .collect()
+    }
+
+    /// `now` is in milliseconds, the ages are measured against it.
+    pub fn render(&self, frame: &mut Frame, area: Rect, world: Option<&World>, now: u64) {
+        let mut rows = self.rows(world, now);
+
+        // missing values sort last
+        let key = |value: Option<f64>| value.unwrap_or(f64::INFINITY);
+        match self.sort_by {
+            SortBy::Symbol => {}
+            SortBy::Spread => rows.sort_by(|a, b| key(a.spread_bps).total_cmp(&key(b.spread_bps))),
+            SortBy::Rate => rows.sort_by(|a, b| b.rate.total_cmp(&a.rate)),
+            SortBy::LastDiff => rows.sort_by_key(|row| row.since_diff.unwrap_or(u64::MAX)),
+            SortBy::Health => rows.sort_by_key(|row| row.health),
+        }
+        if self.reversed {
+            rows.reverse();
+        }
+
+        let optional = |value: Option<f64>, precision: usize| {
+            value.map(|value| format!("{:.*}", precision, value)).unwrap_or("-".to_string())
+        };
+        let age = |age: Option<u64>| {
+            age.map(|age| format!("{:.1}s", age as f64 / 1000.0)).unwrap_or("-".to_string())
+        };
+
+        let rows = rows.into_iter().map(|row| {
+            let mid = row.bid.zip(row.ask).map(|(bid, ask)| (bid + ask) / 2.0);
+            Row::new(vec![
+                row.symbol.to_string(),
+                optional(row.bid, 8),
+                optional(row.ask, 8),
+                optional(mid, 8),
+                optional(row.spread_bps, 2),
+                format!("{:.1}/s", row.rate),
+                age(row.since_diff),
+                age(row.since_full),
+                row.health.label().to_string(),
+            ])
+            .style(Style::default().fg(row.health.color()))
+        });
+
+        let header = ["Symbol", "Bid", "Ask", "Mid", "Spread bps", "Diffs", "Last diff", "Last full", "Health"];
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(16),
+            Constraint::Length(16),
+            Constraint::Length(16),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+        ];
+        let title = format!(
+            "Symbols (sorted by {:?}{}, s to change, r to reverse)",
+            self.sort_by,
+            if self.reversed { ", reversed" } else { "" }
+        );
+
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(Row::new(header).bold())
+                .block(Block::bordered().title(title)),
+            area,
+        );
+    }
+}
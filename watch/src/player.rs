@@ -0,0 +1,174 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, NaiveDateTime, NaiveTime};
+use datatypes::Event;
+use interleave::Interleaver;
+
+/// Where a replay is and how fast it's going, shared between `watch` and
+/// the thread playing the recording.
+#[derive(Debug)]
+pub struct Playback {
+    wall_anchor: Instant,
+    tape_anchor: Option<u64>,
+    speed: f64,
+    paused: bool,
+    // events to play while paused
+    steps: usize,
+    // events before this receive time are played without waiting
+    seek_to: Option<u64>,
+    /// Receive time of the last event played, in milliseconds
+    pub cursor: Option<u64>,
+    pub finished: bool,
+}
+
+impl Playback {
+    pub fn new(speed: f64) -> Self {
+        Self {
+            wall_anchor: Instant::now(),
+            tape_anchor: None,
+            speed,
+            paused: false,
+            steps: 0,
+            seek_to: None,
+            cursor: None,
+            finished: false,
+        }
+    }
+
+    /// Current tape time in milliseconds, None until the first event.
+    pub fn now(&self) -> Option<u64> {
+        let tape_anchor = self.tape_anchor?;
+        if self.paused {
+            return Some(tape_anchor);
+        }
+        Some(tape_anchor + (self.wall_anchor.elapsed().as_millis() as f64 * self.speed) as u64)
+    }
+
+    fn anchor(&mut self, tape_time: u64) {
+        self.tape_anchor = Some(tape_time);
+        self.wall_anchor = Instant::now();
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        if let Some(now) = self.now() {
+            self.anchor(now);
+        }
+        self.speed = speed;
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if let Some(now) = self.now() {
+            self.anchor(now);
+        }
+        self.paused = !self.paused;
+    }
+
+    /// Pause, then play the next event.
+    pub fn step(&mut self) {
+        if !self.paused {
+            self.toggle_pause();
+        }
+        self.steps += 1;
+    }
+
+    /// Play up to `time` as fast as possible, only forwards, see
+    /// [`crate::App`] for going back.
+    pub fn seek(&mut self, time: u64) {
+        self.seek_to = Some(time);
+    }
+
+    pub fn seeking_to(&self) -> Option<u64> {
+        self.seek_to
+    }
+
+    // whether the event at `time` should be played now
+    fn due(&mut self, time: u64) -> bool {
+        if let Some(seek_to) = self.seek_to {
+            if time < seek_to {
+                return true;
+            }
+            // pick up from the target at whatever speed the replay was going
+            self.seek_to = None;
+            self.anchor(seek_to);
+        }
+
+        if self.tape_anchor.is_none() {
+            self.anchor(time);
+        }
+
+        if self.paused {
+            if self.steps == 0 {
+                return false;
+            }
+            self.steps -= 1;
+            self.anchor(time);
+            return true;
+        }
+
+        self.now().is_some_and(|now| now >= time)
+    }
+}
+
+/// The events in recorded data directories, each one held back until the
+/// playback reaches its receive time.
+pub struct Player {
+    events: Interleaver,
+    playback: Arc<Mutex<Playback>>,
+}
+
+impl Player {
+    pub fn new(events: Interleaver, playback: Arc<Mutex<Playback>>) -> Self {
+        Self { events, playback }
+    }
+}
+
+impl Iterator for Player {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(event) = self.events.next() else {
+            self.playback.lock().unwrap().finished = true;
+            return None;
+        };
+
+        loop {
+            // watch lets go of the playback when it starts the replay over
+            if Arc::strong_count(&self.playback) == 1 {
+                return None;
+            }
+            {
+                let mut playback = self.playback.lock().unwrap();
+                if playback.due(event.receive_time) {
+                    playback.cursor = Some(event.receive_time);
+                    return Some(event);
+                }
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+/// A time to jump to: milliseconds since the epoch, `YYYY-MM-DD HH:MM:SS[.fff]`,
+/// or `HH:MM:SS[.fff]` on the cursor's day, all UTC like the rest of `watch`.
+pub fn parse_time(input: &str, cursor: u64) -> Option<u64> {
+    let input = input.trim();
+    if let Ok(ms) = input.parse() {
+        return Some(ms);
+    }
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S%.f") {
+        return u64::try_from(datetime.and_utc().timestamp_millis()).ok();
+    }
+    let time = NaiveTime::parse_from_str(input, "%H:%M:%S%.f").ok()?;
+    let day = DateTime::from_timestamp_millis(cursor as i64)?.date_naive();
+    u64::try_from(day.and_time(time).and_utc().timestamp_millis()).ok()
+}
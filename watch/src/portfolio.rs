@@ -0,0 +1,179 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use datatypes::world_builder::World;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table},
+};
+
+// used to split symbols into base and quote before the exchange info has been seen
+const QUOTE_ASSETS: [&str; 10] = ["USDT", "FDUSD", "USDC", "BUSD", "TUSD", "BTC", "ETH", "BNB", "EUR", "TRY"];
+
+fn split_symbol(world: &World, symbol: &str) -> Option<(String, String)> {
+    if let Some(info) = world.symbol_info.get(symbol) {
+        return Some((info.base_asset.clone(), info.quote_asset.clone()));
+    }
+    QUOTE_ASSETS
+        .iter()
+        .find(|quote| symbol.len() > quote.len() && symbol.ends_with(*quote))
+        .map(|quote| (symbol[..symbol.len() - quote.len()].to_string(), quote.to_string()))
+}
+
+/// Price of one `from` in `to` through the fewest books, going either way
+/// along each pair at its mid price.
+pub fn convert(world: &World, from: &str, to: &str) -> Option<f64> {
+    if from == to {
+        return Some(1.0);
+    }
+
+    // asset -> (other asset, price of one asset in the other)
+    let mut rates: HashMap<String, Vec<(String, f64)>> = HashMap::new();
+    for (symbol, ob) in &world.order_books {
+        let (Some(bid), Some(ask)) = (ob.bids.first(), ob.asks.first()) else {
+            continue;
+        };
+        let Some((base, quote)) = split_symbol(world, symbol) else {
+            continue;
+        };
+        let mid = (bid.price + ask.price) / 2.0;
+        rates.entry(base.clone()).or_default().push((quote.clone(), mid));
+        rates.entry(quote).or_default().push((base, 1.0 / mid));
+    }
+
+    let mut seen = HashSet::from([from.to_string()]);
+    let mut queue = VecDeque::from([(from.to_string(), 1.0)]);
+    while let Some((asset, price)) = queue.pop_front() {
+        for (next, rate) in rates.get(&asset).into_iter().flatten() {
+            if next == to {
+                return Some(price * rate);
+            }
+            if seen.insert(next.clone()) {
+                queue.push_back((next.clone(), price * rate));
+            }
+        }
+    }
+    None
+}
+
+#[derive(Debug)]
+struct Holding {
+    asset: String,
+    free: f64,
+    locked: f64,
+    // in the quote asset, None without a path of books to it
+    price: Option<f64>,
+}
+
+/// The account's balances valued in one quote asset at the books' mid prices.
+#[derive(Debug)]
+pub struct Portfolio {
+    quote: String,
+    holdings: Vec<Holding>,
+    // equity the first time every holding could be valued
+    start_equity: Option<f64>,
+}
+
+impl Portfolio {
+    pub fn new(quote: String) -> Self {
+        Self {
+            quote,
+            holdings: Vec::new(),
+            start_equity: None,
+        }
+    }
+
+    pub fn update(&mut self, world: &World) {
+        let Some(account) = &world.account_information else {
+            return;
+        };
+
+        self.holdings = account
+            .balances
+            .iter()
+            .map(|balance| Holding {
+                asset: balance.asset.clone(),
+                free: balance.free.parse().unwrap_or(0.0),
+                locked: balance.locked.parse().unwrap_or(0.0),
+                price: convert(world, &balance.asset, &self.quote),
+            })
+            .filter(|holding| holding.free + holding.locked != 0.0)
+            .collect();
+
+        if self.start_equity.is_none() && self.holdings.iter().all(|holding| holding.price.is_some()) {
+            self.start_equity = Some(self.equity());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.holdings.clear();
+        self.start_equity = None;
+    }
+
+    fn equity(&self) -> f64 {
+        self.holdings
+            .iter()
+            .filter_map(|holding| holding.price.map(|price| (holding.free + holding.locked) * price))
+            .sum()
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(format!("Portfolio ({})", self.quote));
+        if self.holdings.is_empty() {
+            frame.render_widget(Paragraph::new("No balances in world").block(block), area);
+            return;
+        }
+
+        let mut holdings = self.holdings.iter().collect::<Vec<_>>();
+        // largest first, anything that couldn't be valued last
+        holdings.sort_by(|a, b| {
+            let value = |holding: &Holding| holding.price.map(|price| (holding.free + holding.locked) * price);
+            value(b).unwrap_or(f64::NEG_INFINITY).total_cmp(&value(a).unwrap_or(f64::NEG_INFINITY))
+        });
+
+        let rows = holdings.into_iter().map(|holding| {
+            let value = holding.price.map(|price| (holding.free + holding.locked) * price);
+            let row = Row::new(vec![
+                holding.asset.clone(),
+                format!("{}", holding.free),
+                format!("{}", holding.locked),
+                holding.price.map(|price| format!("{:.8}", price)).unwrap_or("-".to_string()),
+                value.map(|value| format!("{:.2}", value)).unwrap_or("no price".to_string()),
+            ]);
+            if value.is_none() { row.style(Style::default().fg(Color::Yellow)) } else { row }
+        });
+
+        let unpriced = self.holdings.iter().filter(|holding| holding.price.is_none()).count();
+        let equity = self.equity();
+        let mut summary = vec![Line::from(format!("Equity {:.2} {}", equity, self.quote)).bold()];
+        if let Some(start) = self.start_equity {
+            let change = equity - start;
+            let percent = if start != 0.0 { change / start * 100.0 } else { 0.0 };
+            let color = if change < 0.0 { Color::Red } else { Color::Green };
+            summary.push(Line::styled(format!("{:+.2} ({:+.2}%) this session", change, percent), color));
+        }
+        if unpriced > 0 {
+            summary.push(Line::styled(format!("{} assets without a price", unpriced), Color::Yellow));
+        }
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let [table_area, summary_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(summary.len() as u16)]).areas(inner);
+
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ];
+        frame.render_widget(
+            Table::new(rows, widths).header(Row::new(["Asset", "Free", "Locked", "Price", "Value"]).bold()),
+            table_area,
+        );
+        frame.render_widget(Paragraph::new(summary), summary_area);
+    }
+}
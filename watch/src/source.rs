@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Read};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::Duration;
+
+// how often followed directories are checked for new files, e.g. a symbol
+// added to the recorder or a new day's file
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where `watch` reads its events from.
+#[derive(Debug, Clone)]
+pub enum Source {
+    Stdin,
+    /// Recorded files and data directories, followed as they grow
+    Files(Vec<PathBuf>),
+    Tcp(String),
+    Unix(PathBuf),
+    /// Data directories played back from the start, see [`crate::player`]
+    Replay(Vec<String>),
+}
+
+impl Source {
+    /// `tcp://host:port`, `unix:///path/to/socket`, a data directory, files,
+    /// or `-` (or nothing) for stdin.
+    pub fn parse(args: &[String]) -> io::Result<Self> {
+        match args {
+            [] => return Ok(Source::Stdin),
+            [arg] if arg == "-" => return Ok(Source::Stdin),
+            [arg] => {
+                if let Some(addr) = arg.strip_prefix("tcp://") {
+                    return Ok(Source::Tcp(addr.to_string()));
+                }
+                if let Some(path) = arg.strip_prefix("unix://") {
+                    return Ok(Source::Unix(PathBuf::from(path)));
+                }
+            }
+            _ => (),
+        }
+
+        let paths = args.iter().map(PathBuf::from).collect::<Vec<_>>();
+        // fail up front on a directory that can't be listed
+        list_files(&paths)?;
+        Ok(Source::Files(paths))
+    }
+
+    /// The lines of the source, and for files the [`Follower`] producing them,
+    /// which has to be kept for as long as they're read.
+    pub fn open(&self) -> io::Result<(Box<dyn BufRead + Send>, Option<Follower>)> {
+        Ok(match self {
+            Source::Stdin => (Box::new(BufReader::new(io::stdin())), None),
+            Source::Files(paths) => {
+                let (follower, lines) = Follower::start(paths.clone())?;
+                (Box::new(BufReader::new(lines)), Some(follower))
+            }
+            Source::Tcp(addr) => (Box::new(BufReader::new(TcpStream::connect(addr)?)), None),
+            Source::Unix(path) => (Box::new(BufReader::new(UnixStream::connect(path)?)), None),
+            Source::Replay(_) => {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "recordings are played, not read"));
+            }
+        })
+    }
+}
+
+// the files named and those in the directories named, in order
+fn list_files(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries = std::fs::read_dir(path)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .collect::<Vec<_>>();
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// `tail -f`s recorded files, and starts on files that turn up in the
+/// directories later. The `tail`s are killed when this is dropped.
+#[derive(Debug)]
+pub struct Follower {
+    // `None` once dropped, so a tail started while dropping is killed too
+    tails: Arc<Mutex<Option<Vec<Child>>>>,
+}
+
+impl Follower {
+    fn start(paths: Vec<PathBuf>) -> io::Result<(Self, Lines)> {
+        let (line_tx, line_rx) = channel();
+        // the files there at the start show their last lines, like `tail -f`
+        let files = list_files(&paths)?;
+        let mut started = Vec::new();
+        if !files.is_empty() {
+            started.push(tail(&files, false, line_tx.clone())?);
+        }
+        let tails = Arc::new(Mutex::new(Some(started)));
+
+        let known = files.into_iter().collect();
+        let watched = Arc::downgrade(&tails);
+        thread::spawn(move || rescan(paths, known, watched, line_tx));
+
+        let lines = Lines {
+            lines: line_rx,
+            buffer: Vec::new(),
+            read: 0,
+        };
+        Ok((Self { tails }, lines))
+    }
+}
+
+impl Drop for Follower {
+    fn drop(&mut self) {
+        for child in self.tails.lock().unwrap().take().into_iter().flatten() {
+            stop(child);
+        }
+    }
+}
+
+fn stop(mut child: Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// spawn `tail` on `files`, forwarding its output a line at a time so lines
+// from different tails never interleave
+fn tail(files: &[PathBuf], from_start: bool, line_tx: Sender<String>) -> io::Result<Child> {
+    let mut command = Command::new("tail");
+    command.arg("-fq");
+    if from_start {
+        command.args(["-n", "+1"]);
+    }
+    let mut child = command.args(files).stdout(Stdio::piped()).stderr(Stdio::null()).spawn()?;
+
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    thread::spawn(move || {
+        for line in stdout.lines().map_while(|line| line.ok()) {
+            if line_tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(child)
+}
+
+// follow new files from their first line until the follower is dropped
+fn rescan(
+    paths: Vec<PathBuf>,
+    mut known: HashSet<PathBuf>,
+    tails: Weak<Mutex<Option<Vec<Child>>>>,
+    line_tx: Sender<String>,
+) {
+    loop {
+        thread::sleep(RESCAN_INTERVAL);
+        let Some(tails) = tails.upgrade() else {
+            return;
+        };
+
+        let Ok(files) = list_files(&paths) else {
+            continue;
+        };
+        let new = files.into_iter().filter(|file| !known.contains(file)).collect::<Vec<_>>();
+        if new.is_empty() {
+            continue;
+        }
+
+        let Ok(child) = tail(&new, true, line_tx.clone()) else {
+            continue;
+        };
+        match tails.lock().unwrap().as_mut() {
+            Some(tails) => tails.push(child),
+            None => return stop(child),
+        }
+        known.extend(new);
+    }
+}
+
+// the lines every tail of a follower has written, in the order they arrived
+struct Lines {
+    lines: Receiver<String>,
+    buffer: Vec<u8>,
+    read: usize,
+}
+
+impl Read for Lines {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.read == self.buffer.len() {
+            // every tail is gone once the follower has been dropped
+            let Ok(line) = self.lines.recv() else {
+                return Ok(0);
+            };
+            self.buffer = line.into_bytes();
+            self.buffer.push(b'\n');
+            self.read = 0;
+        }
+
+        let n = buf.len().min(self.buffer.len() - self.read);
+        buf[..n].copy_from_slice(&self.buffer[self.read..self.read + n]);
+        self.read += n;
+        Ok(n)
+    }
+}